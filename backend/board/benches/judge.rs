@@ -0,0 +1,82 @@
+//! ビットマスクによる判定と素朴な判定の比較
+//!
+//! 全参加者の数字盤で抽選された数字を開け、その度にビンゴとリーチを判定する。
+//! `cargo bench -p board`で実行する。
+
+use board::board::Board;
+use board::generate::NumberRange;
+use board::judge::naive;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+/// シミュレーションする参加者数
+const PLAYERS: u64 = 300;
+
+fn setup(size: usize) -> (Vec<Board>, Vec<usize>) {
+    let boards = (0..PLAYERS)
+        .map(|id| Board::new(id, size).unwrap())
+        .collect();
+    let mut draws: Vec<usize> = NumberRange::for_size(size).usable(size).collect();
+    draws.shuffle(&mut StdRng::seed_from_u64(0));
+
+    (boards, draws)
+}
+
+fn judge(c: &mut Criterion) {
+    let mut group = c.benchmark_group("judge");
+    group.sample_size(10);
+
+    for size in [5, 7, 9, 11, 13, 15] {
+        let (boards, draws) = setup(size);
+
+        group.bench_with_input(BenchmarkId::new("bitmask", size), &size, |b, _| {
+            b.iter_batched(
+                || boards.clone(),
+                |mut boards| {
+                    for &number in &draws {
+                        for board in &mut boards {
+                            board.open(number).unwrap();
+                        }
+                    }
+                    boards
+                },
+                criterion::BatchSize::LargeInput,
+            )
+        });
+
+        // 以前の`Board::open`と同じく、開けた後にビンゴとリーチを判定する
+        let numbers: Vec<Vec<Vec<usize>>> = boards
+            .iter()
+            .map(|board| board.numbers().to_vec())
+            .collect();
+        group.bench_with_input(BenchmarkId::new("naive", size), &size, |b, _| {
+            b.iter_batched(
+                || vec![vec![]; numbers.len()],
+                |mut opened: Vec<Vec<usize>>| {
+                    for &number in &draws {
+                        for (numbers, opened) in numbers.iter().zip(&mut opened) {
+                            if opened.contains(&number)
+                                || !numbers.iter().flatten().any(|&x| x == number)
+                            {
+                                continue;
+                            }
+
+                            opened.push(number);
+                            naive::judge_bingo(numbers, opened);
+                            naive::judge_reach(numbers, opened);
+                        }
+                    }
+                    opened
+                },
+                criterion::BatchSize::LargeInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, judge);
+criterion_main!(benches);
@@ -0,0 +1,214 @@
+use crate::board::Board;
+use crate::pattern::Pattern;
+use serde::{Deserialize, Serialize};
+
+/// リーチの種類
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ReachKind {
+    NONE,
+    /// リーチ
+    SINGLE,
+    /// ダブルリーチ
+    DOUBLE,
+    /// トリプルリーチ
+    TRIPLE,
+    /// 4ライン以上のリーチ
+    MULTIPLE,
+}
+
+impl ReachKind {
+    /// リーチになっているラインの数から種類を決める
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use board::analysis::ReachKind;
+    /// assert_eq!(ReachKind::from_count(0), ReachKind::NONE);
+    /// assert_eq!(ReachKind::from_count(2), ReachKind::DOUBLE);
+    /// assert_eq!(ReachKind::from_count(5), ReachKind::MULTIPLE);
+    /// ```
+    pub fn from_count(count: usize) -> Self {
+        match count {
+            0 => ReachKind::NONE,
+            1 => ReachKind::SINGLE,
+            2 => ReachKind::DOUBLE,
+            3 => ReachKind::TRIPLE,
+            _ => ReachKind::MULTIPLE,
+        }
+    }
+}
+
+/// 1ラインの進み具合
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct LineProgress {
+    pub pattern: Pattern,
+    /// ラインに書かれている数字
+    pub numbers: Vec<usize>,
+    /// 揃うまでに開ける必要があるマスの数
+    pub remaining: usize,
+}
+
+/// 数字盤の分析結果
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct Analysis {
+    /// 全ラインの進み具合 (`Board::patterns`の順)
+    pub lines: Vec<LineProgress>,
+    /// 次に開ければビンゴになる数字 (昇順)
+    pub winning_numbers: Vec<usize>,
+    /// リーチになっているラインの数
+    pub reach_count: usize,
+    pub reach_kind: ReachKind,
+}
+
+impl Analysis {
+    /// 揃うまでにあとn個のラインの数
+    pub fn count_away(&self, n: usize) -> usize {
+        self.lines.iter().filter(|line| line.remaining == n).count()
+    }
+
+    /// 揃うまでに最も近いラインのあと何個か
+    /// 全てのラインが揃っている場合は0
+    pub fn closest(&self) -> usize {
+        self.lines
+            .iter()
+            .map(|line| line.remaining)
+            .filter(|&remaining| remaining > 0)
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+impl Board {
+    /// 各ラインの進み具合とビンゴになる数字を分析する
+    ///
+    /// # 例
+    /// ```
+    /// # use board::analysis::ReachKind;
+    /// # use board::board::Board;
+    /// // [
+    /// //   [15, 18, 45],
+    /// //   [11, 0, 36],
+    /// //   [7, 19, 41],
+    /// // ]
+    /// let mut board = Board::new(1, 3).unwrap();
+    /// board.open(15).unwrap();
+    ///
+    /// let analysis = board.analyze();
+    /// assert_eq!(analysis.winning_numbers, vec![41]);
+    /// assert_eq!(analysis.reach_kind, ReachKind::SINGLE);
+    /// ```
+    pub fn analyze(&self) -> Analysis {
+        let mut winning_numbers = vec![];
+        let mut reach_count = 0;
+
+        let lines: Vec<LineProgress> = self
+            .marks
+            .lines()
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                let remaining = self.marks.missing_count(i);
+                if remaining == 1 {
+                    reach_count += 1;
+                    winning_numbers.extend(
+                        line.cells
+                            .iter()
+                            .filter(|&&cell| !self.marks.is_marked(cell))
+                            .map(|&cell| self.number_at(cell)),
+                    );
+                }
+
+                LineProgress {
+                    pattern: line.pattern.clone(),
+                    numbers: self.line_numbers(line),
+                    remaining,
+                }
+            })
+            .collect();
+
+        winning_numbers.sort_unstable();
+        winning_numbers.dedup();
+
+        Analysis {
+            lines,
+            winning_numbers,
+            reach_count,
+            reach_kind: ReachKind::from_count(reach_count),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::NumberRange;
+
+    #[test]
+    fn it_counts_remaining_cells() {
+        // [
+        //   [15, 30, 31, 48, 75],
+        //   [11, 21, 45, 59, 69],
+        //   [ 7, 26,  0, 51, 70],
+        //   [ 3, 23, 37, 46, 62],
+        //   [ 4, 19, 33, 55, 63]
+        // ]
+        let board = Board::new(1, 5).unwrap();
+        let analysis = board.analyze();
+
+        assert_eq!(analysis.lines.len(), 12);
+        // フリーマスを通る4ラインはあと4個
+        assert_eq!(analysis.count_away(4), 4);
+        assert_eq!(analysis.count_away(5), 8);
+        assert_eq!(analysis.closest(), 4);
+        assert!(analysis.winning_numbers.is_empty());
+        assert_eq!(analysis.reach_kind, ReachKind::NONE);
+    }
+
+    #[test]
+    fn it_detects_double_reach() {
+        let mut board = Board::new(1, 5).unwrap();
+        // 左上からの対角線と3行目が同時にリーチ
+        for number in [15, 21, 46, 7, 26] {
+            board.open(number).unwrap();
+        }
+        assert_eq!(board.open(51).unwrap().reach_count, 2);
+
+        let analysis = board.analyze();
+        assert_eq!(analysis.reach_count, 2);
+        assert_eq!(analysis.reach_kind, ReachKind::DOUBLE);
+        assert_eq!(analysis.winning_numbers, vec![63, 70]);
+    }
+
+    #[test]
+    fn it_shares_winning_number_between_lines() {
+        let mut board = Board::new(1, 5).unwrap();
+        // 1行目と1列目が共に15だけ開いていない
+        for number in [30, 31, 48, 75, 11, 7, 3, 4] {
+            board.open(number).unwrap();
+        }
+
+        let analysis = board.analyze();
+        assert_eq!(analysis.reach_kind, ReachKind::DOUBLE);
+        assert_eq!(analysis.winning_numbers, vec![15]);
+    }
+
+    #[test]
+    fn it_analyzes_custom_patterns() {
+        let range = NumberRange::for_size(5);
+        let mut board = Board::with_patterns(1, 5, range, vec![Pattern::CORNERS]).unwrap();
+        for number in [15, 75, 4] {
+            board.open(number).unwrap();
+        }
+
+        let analysis = board.analyze();
+        assert_eq!(
+            analysis.lines,
+            vec![LineProgress {
+                pattern: Pattern::CORNERS,
+                numbers: vec![15, 75, 4, 63],
+                remaining: 1,
+            }]
+        );
+        assert_eq!(analysis.winning_numbers, vec![63]);
+    }
+}
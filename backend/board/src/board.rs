@@ -1,41 +1,300 @@
-use crate::generate;
-use serde::Serialize;
+use crate::cell::{Cell, CellKind};
+use crate::dice::DiceSet;
+use crate::errors::Error;
+use crate::generate::{self, NumberRange};
+use crate::mask::{Line, Marks};
+use crate::pattern::Pattern;
+use crate::tile::Tile;
+use serde::{Serialize, Serializer};
+use std::ops::RangeInclusive;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum BoardState {
     BINGO(Vec<Vec<usize>>),
     REACH(Vec<Vec<usize>>),
     NONE,
 }
 
-#[derive(Serialize, Clone, PartialEq, Debug)]
+/// 数字を開けようとした結果
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OpenStatus {
+    /// 新しく開けた
+    OPENED,
+    /// 既に開けていたので何も変わらなかった
+    DUPLICATED,
+    /// 数字盤に書かれていない数字だった
+    MISSING,
+}
+
+/// `Board::open`の結果
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpenResult {
+    pub status: OpenStatus,
+    /// 開けた後の数字盤の状態 (既に揃っていたラインも含む)
+    pub state: BoardState,
+    /// 今回新しく揃ったライン
+    pub new_bingo: Vec<Vec<usize>>,
+    /// 今回新しく揃ったラインのパターン (重複なし)
+    pub new_patterns: Vec<Pattern>,
+    /// 今回新しくリーチになったライン
+    pub new_reach: Vec<Vec<usize>>,
+    /// 開けた後にリーチになっているラインの数 (ダブルリーチなら2)
+    pub reach_count: usize,
+}
+
+/// APIではマスの値は`kind`に応じた表記になる (`Cell`)
+#[derive(Clone, PartialEq, Debug)]
 pub struct Board {
-    /// UserのSnowflake ID + SessionID
+    /// 数字盤のID (数字の並びを決めるシード)
     pub id: u64,
     /// 数字盤のサイズ
     pub size: usize,
-    /// 数字盤に書かれている数字
-    pub numbers: Vec<Vec<usize>>,
-    /// 開けた数字
-    pub opened: Vec<usize>,
+    /// 数字盤に書かれうる数字の範囲
+    pub range: NumberRange,
+    /// ビンゴと認められるパターン
+    pub patterns: Vec<Pattern>,
+    /// 数字を決めるサイコロの組 (サイコロで作った数字盤のみ)
+    pub dice: Option<DiceSet>,
+    /// マスに書かれるものの種類
+    pub kind: CellKind,
+    /// 表示用の各マスの表記 (ゲームモードで数字の表し方が変わる場合のみ)
+    pub labels: Option<Vec<Vec<String>>>,
+    /// 各マスの値
+    cells: Vec<Vec<Cell>>,
+    /// 開けたマス (開けた順)
+    opened: Vec<Cell>,
+    /// 開けられる数字の範囲 (範囲外の数字を開けるとエラーになる)
+    accepted: RangeInclusive<usize>,
+    /// 判定に使う開いているマスの集合
+    pub(crate) marks: Marks,
+}
+
+/// patternsが数字盤に使えるか検証する
+fn validate_patterns(size: usize, patterns: &[Pattern]) -> Result<(), Error> {
+    if patterns.is_empty() {
+        return Err(Error::NoPatterns);
+    }
+    for pattern in patterns {
+        pattern.validate(size)?;
+    }
+
+    Ok(())
 }
 
 impl Board {
-    pub fn new(id: u64, size: usize) -> Result<Self, String> {
+    /// 標準の範囲 (1列あたり15個) の数字が書かれた数字盤を作る
+    pub fn new(id: u64, size: usize) -> Result<Self, Error> {
+        Self::with_range(id, size, NumberRange::for_size(size))
+    }
+
+    /// rangeの範囲の数字が書かれた数字盤を作る
+    pub fn with_range(id: u64, size: usize, range: NumberRange) -> Result<Self, Error> {
+        Self::with_patterns(id, size, range, vec![Pattern::LINE])
+    }
+
+    /// rangeの範囲の数字が書かれ、patternsのいずれかが揃うとビンゴになる数字盤を作る
+    pub fn with_patterns(
+        id: u64,
+        size: usize,
+        range: NumberRange,
+        patterns: Vec<Pattern>,
+    ) -> Result<Self, Error> {
+        validate_patterns(size, &patterns)?;
+
+        let mut rng = rand::SeedableRng::seed_from_u64(id);
+        let numbers = generate::generate_board_numbers(&mut rng, size, &range)?;
+        let mut board = Self::build(id, size, range, None, patterns, CellKind::NUMBER, numbers);
+        // 等分できなかった端数は書かれないので開けられない
+        board.accepted = range.usable(size);
+
+        Ok(board)
+    }
+
+    /// diceで出る可能性のある数字が書かれ、patternsのいずれかが揃うとビンゴになる数字盤を作る
+    pub fn with_dice(
+        id: u64,
+        size: usize,
+        dice: DiceSet,
+        patterns: Vec<Pattern>,
+    ) -> Result<Self, Error> {
+        validate_patterns(size, &patterns)?;
+
         let mut rng = rand::SeedableRng::seed_from_u64(id);
-        let numbers = generate::generate_board_numbers(&mut rng, size)?;
-        Ok(Self {
+        let numbers = generate::generate_dice_board_numbers(&mut rng, size, &dice)?;
+        Ok(Self::build(
             id,
             size,
+            dice.range(),
+            Some(dice),
+            patterns,
+            CellKind::NUMBER,
             numbers,
+        ))
+    }
+
+    /// 麻雀牌が書かれ、patternsのいずれかが揃うとビンゴになる数字盤を作る
+    /// 牌は番号 (`Tile::code`) を数字として開ける
+    pub fn with_tiles(id: u64, size: usize, patterns: Vec<Pattern>) -> Result<Self, Error> {
+        validate_patterns(size, &patterns)?;
+
+        let mut rng = rand::SeedableRng::seed_from_u64(id);
+        let numbers = generate::generate_tile_board_numbers(&mut rng, size)?;
+        let range = NumberRange {
+            min: 1,
+            max: Tile::KINDS,
+        };
+        Ok(Self::build(
+            id,
+            size,
+            range,
+            None,
+            patterns,
+            CellKind::TILE,
+            numbers,
+        ))
+    }
+
+    /// 生成された数字をkindのマスの値にして数字盤を作る
+    fn build(
+        id: u64,
+        size: usize,
+        range: NumberRange,
+        dice: Option<DiceSet>,
+        patterns: Vec<Pattern>,
+        kind: CellKind,
+        numbers: Vec<Vec<usize>>,
+    ) -> Self {
+        let cells: Vec<Vec<Cell>> = numbers
+            .into_iter()
+            .map(|row| row.into_iter().map(|number| kind.cell(number)).collect())
+            .collect();
+
+        Self {
+            id,
+            size,
+            range,
+            marks: Marks::new(&cells, &patterns),
+            patterns,
+            dice,
+            kind,
+            labels: None,
+            cells,
             opened: vec![],
+            accepted: range.min..=range.max,
+        }
+    }
+
+    /// 数字盤の各マスの値
+    pub fn cells(&self) -> &[Vec<Cell>] {
+        &self.cells
+    }
+
+    /// 開けたマス (開けた順)
+    pub fn opened_cells(&self) -> &[Cell] {
+        &self.opened
+    }
+
+    /// 数字盤に書かれている数字 (`Cell::number`)
+    pub fn numbers(&self) -> Vec<Vec<usize>> {
+        self.cells
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.number()).collect())
+            .collect()
+    }
+
+    /// 開けた数字 (開けた順)
+    pub fn opened(&self) -> Vec<usize> {
+        self.opened.iter().map(|cell| cell.number()).collect()
+    }
+
+    /// マスの値
+    pub(crate) fn cell_at(&self, cell: usize) -> Cell {
+        self.cells[cell / self.size][cell % self.size]
+    }
+
+    /// マスに書かれている数字
+    pub(crate) fn number_at(&self, cell: usize) -> usize {
+        self.cell_at(cell).number()
+    }
+
+    /// ラインに書かれている数字
+    pub(crate) fn line_numbers(&self, line: &Line) -> Vec<usize> {
+        line.cells
+            .iter()
+            .map(|&cell| self.number_at(cell))
+            .collect()
+    }
+
+    /// 数字を開ける
+    /// 数字は`kind`に応じたマスの値として扱われる (麻雀牌なら牌の番号)
+    pub fn open(&mut self, number: usize) -> Result<OpenResult, Error> {
+        self.open_cell(self.kind.cell(number))
+    }
+
+    /// マスの値を開ける
+    ///
+    /// 数字盤に書かれうる範囲外の値はエラーになる。
+    /// 既に開けた値や数字盤に書かれていない値を開けても数字盤は変わらない。
+    pub fn open_cell(&mut self, value: Cell) -> Result<OpenResult, Error> {
+        let number = value.number();
+        if value == Cell::FREE
+            || value.kind() != Some(self.kind)
+            || !self.accepted.contains(&number)
+        {
+            return Err(Error::InvalidNumber { number });
+        }
+
+        let cell = match self.marks.position(value) {
+            Some(cell) if self.marks.is_marked(cell) => {
+                return Ok(self.unchanged(OpenStatus::DUPLICATED))
+            }
+            Some(cell) => cell,
+            None => return Ok(self.unchanged(OpenStatus::MISSING)),
+        };
+
+        self.marks.mark(cell);
+        self.opened.push(value);
+
+        // 状態が変わりうるのは開けたマスを通るラインだけ
+        let (mut new_bingo, mut new_reach, mut new_patterns) = (vec![], vec![], vec![]);
+        for &i in self.marks.lines_through(cell) {
+            let line = &self.marks.lines()[i];
+            match self.marks.missing_count(i) {
+                0 => {
+                    new_bingo.push(self.line_numbers(line));
+                    if !new_patterns.contains(&line.pattern) {
+                        new_patterns.push(line.pattern.clone());
+                    }
+                }
+                1 => new_reach.push(self.line_numbers(line)),
+                _ => {}
+            }
+        }
+
+        Ok(OpenResult {
+            status: OpenStatus::OPENED,
+            state: self.state(),
+            new_bingo,
+            new_reach,
+            reach_count: self.marks.reach_count(),
+            new_patterns,
         })
     }
 
-    pub fn open(&mut self, number: usize) -> BoardState {
-        // TODO: バリデーション
-        self.opened.push(number);
+    /// 数字盤が変わらなかった場合の結果
+    fn unchanged(&self, status: OpenStatus) -> OpenResult {
+        OpenResult {
+            status,
+            state: self.state(),
+            new_bingo: vec![],
+            new_reach: vec![],
+            reach_count: self.marks.reach_count(),
+            new_patterns: vec![],
+        }
+    }
 
+    /// 現在の数字盤の状態 (`Marks`が持つ揃っているラインとリーチのラインから作る)
+    pub fn state(&self) -> BoardState {
         if let Some(bingo) = self.judge_bingo() {
             return BoardState::BINGO(bingo);
         }
@@ -48,6 +307,38 @@ impl Board {
     }
 }
 
+impl Serialize for Board {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct View<'a> {
+            id: u64,
+            size: usize,
+            range: &'a NumberRange,
+            patterns: &'a [Pattern],
+            #[serde(skip_serializing_if = "Option::is_none")]
+            dice: &'a Option<DiceSet>,
+            kind: CellKind,
+            numbers: &'a [Vec<Cell>],
+            #[serde(skip_serializing_if = "Option::is_none")]
+            labels: &'a Option<Vec<Vec<String>>>,
+            opened: &'a [Cell],
+        }
+
+        View {
+            id: self.id,
+            size: self.size,
+            range: &self.range,
+            patterns: &self.patterns,
+            dice: &self.dice,
+            kind: self.kind,
+            numbers: &self.cells,
+            labels: &self.labels,
+            opened: &self.opened,
+        }
+        .serialize(serializer)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -62,15 +353,225 @@ mod tests {
         //   [ 4, 19, 33, 55, 63]
         // ]
         let mut board = Board::new(1, 5).unwrap();
-        assert_eq!(board.open(15), BoardState::NONE);
-        assert_eq!(board.open(21), BoardState::NONE);
+        assert_eq!(board.open(15).unwrap().state, BoardState::NONE);
+        assert_eq!(board.open(21).unwrap().state, BoardState::NONE);
         assert_eq!(
-            board.open(63),
+            board.open(63).unwrap().state,
             BoardState::REACH(vec![vec![15, 21, 0, 46, 63]])
         );
         assert_eq!(
-            board.open(46),
+            board.open(46).unwrap().state,
             BoardState::BINGO(vec![vec![15, 21, 0, 46, 63]])
         );
     }
+
+    #[test]
+    fn it_reports_only_new_lines() {
+        let mut board = Board::new(1, 5).unwrap();
+        for number in [15, 21, 63] {
+            board.open(number).unwrap();
+        }
+
+        let line = vec![15, 21, 0, 46, 63];
+        assert_eq!(
+            board.open(46).unwrap(),
+            OpenResult {
+                status: OpenStatus::OPENED,
+                state: BoardState::BINGO(vec![line.clone()]),
+                new_bingo: vec![line.clone()],
+                new_reach: vec![],
+                reach_count: 0,
+                new_patterns: vec![Pattern::LINE],
+            }
+        );
+
+        // 既に揃っているラインは新しく揃ったことにはならない
+        let result = board.open(30).unwrap();
+        assert_eq!(result.state, BoardState::BINGO(vec![line]));
+        assert!(result.new_bingo.is_empty());
+    }
+
+    #[test]
+    fn it_reports_new_reach() {
+        let mut board = Board::new(1, 5).unwrap();
+        for number in [15, 21] {
+            assert!(board.open(number).unwrap().new_reach.is_empty());
+        }
+
+        assert_eq!(
+            board.open(63).unwrap().new_reach,
+            vec![vec![15, 21, 0, 46, 63]]
+        );
+    }
+
+    #[test]
+    fn it_opens_idempotently() {
+        let mut board = Board::new(1, 5).unwrap();
+        board.open(15).unwrap();
+
+        let result = board.open(15).unwrap();
+        assert_eq!(result.status, OpenStatus::DUPLICATED);
+        assert_eq!(result.state, BoardState::NONE);
+        assert_eq!(board.opened(), [15]);
+    }
+
+    #[test]
+    fn it_ignores_number_not_on_board() {
+        let mut board = Board::new(1, 5).unwrap();
+
+        assert_eq!(board.open(1).unwrap().status, OpenStatus::MISSING);
+        assert!(board.opened().is_empty());
+    }
+
+    #[test]
+    fn it_judges_corners_pattern() {
+        // [
+        //   [15, 30, 31, 48, 75],
+        //   ...
+        //   [ 4, 19, 33, 55, 63]
+        // ]
+        let range = NumberRange::for_size(5);
+        let mut board = Board::with_patterns(1, 5, range, vec![Pattern::CORNERS]).unwrap();
+        for number in [15, 75, 4] {
+            assert!(board.open(number).unwrap().new_patterns.is_empty());
+        }
+        assert_eq!(board.state(), BoardState::REACH(vec![vec![15, 75, 4, 63]]));
+
+        let result = board.open(63).unwrap();
+        assert_eq!(result.new_bingo, vec![vec![15, 75, 4, 63]]);
+        assert_eq!(result.new_patterns, vec![Pattern::CORNERS]);
+    }
+
+    #[test]
+    fn it_ignores_lines_outside_active_patterns() {
+        let range = NumberRange::for_size(5);
+        let mut board = Board::with_patterns(1, 5, range, vec![Pattern::FRAME]).unwrap();
+
+        // 1列目は揃うがLINEは有効ではない
+        for number in [15, 11, 7, 3, 4] {
+            assert!(board.open(number).unwrap().new_bingo.is_empty());
+        }
+        assert!(board.judge_patterns().is_empty());
+    }
+
+    #[test]
+    fn it_reports_each_pattern_once() {
+        let range = NumberRange::for_size(5);
+        let patterns = vec![Pattern::LINE, Pattern::BLACKOUT];
+        let mut board = Board::with_patterns(1, 5, range, patterns).unwrap();
+
+        let numbers: Vec<usize> = board.numbers().iter().flatten().copied().collect();
+        let mut completed = vec![];
+        for number in numbers.into_iter().filter(|&number| number != 0) {
+            completed.extend(board.open(number).unwrap().new_patterns);
+        }
+
+        // 最後に開けたマスで複数のラインと全マスが同時に揃う
+        assert_eq!(completed.last(), Some(&Pattern::BLACKOUT));
+        assert_eq!(
+            board.judge_patterns(),
+            vec![Pattern::LINE, Pattern::BLACKOUT]
+        );
+    }
+
+    #[test]
+    fn it_rejects_invalid_patterns() {
+        let range = NumberRange::for_size(5);
+
+        assert_eq!(
+            Board::with_patterns(1, 5, range, vec![]),
+            Err(Error::NoPatterns)
+        );
+        assert_eq!(
+            Board::with_patterns(
+                1,
+                5,
+                range,
+                vec![Pattern::CUSTOM {
+                    cells: vec![vec![true]]
+                }]
+            ),
+            Err(Error::InvalidPattern { size: 5 })
+        );
+    }
+
+    #[test]
+    fn it_rejects_number_out_of_custom_range() {
+        let mut board = Board::with_range(1, 3, NumberRange { min: 50, max: 58 }).unwrap();
+
+        assert!(board.open(49).is_err());
+        assert!(board.open(59).is_err());
+        assert!(board.open(board.numbers()[0][0]).is_ok());
+    }
+
+    #[test]
+    fn it_rejects_number_out_of_range() {
+        let mut board = Board::new(1, 5).unwrap();
+
+        assert_eq!(board.open(0), Err(Error::InvalidNumber { number: 0 }));
+        assert_eq!(board.open(76), Err(Error::InvalidNumber { number: 76 }));
+        assert!(board.opened().is_empty());
+    }
+
+    #[test]
+    fn it_opens_dice_board() {
+        let dice = DiceSet::default();
+        let mut board = Board::with_dice(1, 5, dice, vec![Pattern::LINE]).unwrap();
+        let number = board.numbers()[0][0];
+
+        assert_eq!(board.range, NumberRange { min: 3, max: 60 });
+        assert_eq!(board.open(number).unwrap().status, OpenStatus::OPENED);
+        assert_eq!(board.open(60).map(|_| ()), Ok(()));
+        assert_eq!(board.open(2), Err(Error::InvalidNumber { number: 2 }));
+    }
+
+    #[test]
+    fn it_opens_tile_board_by_code() {
+        let mut board = Board::with_tiles(1, 5, vec![Pattern::LINE]).unwrap();
+        let code = board.numbers()[0][0];
+        let Cell::TILE(tile) = board.cells()[0][0] else {
+            panic!("tile is missing");
+        };
+
+        assert_eq!(tile.code(), code);
+        assert_eq!(board.open(code).unwrap().status, OpenStatus::OPENED);
+        assert_eq!(board.open(35), Err(Error::InvalidNumber { number: 35 }));
+    }
+
+    #[test]
+    fn it_opens_typed_cells() {
+        let mut board = Board::with_tiles(1, 5, vec![Pattern::LINE]).unwrap();
+        let tile = board.cells()[0][0];
+
+        assert_eq!(board.open_cell(tile).unwrap().status, OpenStatus::OPENED);
+        assert_eq!(board.opened_cells(), &[tile]);
+        assert_eq!(board.opened(), vec![tile.number()]);
+
+        // 牌の数字盤には数字のマスはない
+        let number = Cell::NUMBER(tile.number());
+        assert_eq!(
+            board.open_cell(number),
+            Err(Error::InvalidNumber {
+                number: tile.number()
+            })
+        );
+        assert_eq!(
+            board.open_cell(Cell::FREE),
+            Err(Error::InvalidNumber { number: 0 })
+        );
+    }
+
+    #[test]
+    fn it_serializes_tile_notation() {
+        let mut board = Board::with_tiles(1, 3, vec![Pattern::LINE]).unwrap();
+        let code = board.numbers()[0][0];
+        board.open(code).unwrap();
+
+        let json = serde_json::to_value(&board).unwrap();
+        let tile = Tile::from_code(code).unwrap().to_string();
+        assert_eq!(json["kind"], "TILE");
+        assert_eq!(json["numbers"][0][0], tile.as_str());
+        assert_eq!(json["numbers"][1][1], 0);
+        assert_eq!(json["opened"][0], tile.as_str());
+    }
 }
@@ -0,0 +1,116 @@
+use crate::tile::Tile;
+use serde::{Deserialize, Serialize, Serializer};
+
+/// 数字盤のマスに書かれるものの種類
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum CellKind {
+    /// 数字
+    #[default]
+    NUMBER,
+    /// 麻雀牌 (数字は牌の番号)
+    TILE,
+}
+
+impl CellKind {
+    /// 数字盤に書かれている数字をマスの値にする
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use board::cell::{Cell, CellKind};
+    /// assert_eq!(CellKind::NUMBER.cell(15), Cell::NUMBER(15));
+    /// assert_eq!(CellKind::TILE.cell(0), Cell::FREE);
+    /// assert_eq!(CellKind::TILE.cell(28).to_string(), "東");
+    /// ```
+    pub fn cell(self, number: usize) -> Cell {
+        match (self, number) {
+            (_, 0) => Cell::FREE,
+            (CellKind::NUMBER, _) => Cell::NUMBER(number),
+            (CellKind::TILE, _) => Tile::from_code(number)
+                .map(Cell::TILE)
+                .unwrap_or(Cell::NUMBER(number)),
+        }
+    }
+}
+
+/// 数字盤の1マスの値
+///
+/// APIではフリーマスは0、数字はそのまま、牌は`1m`のような表記になる。
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Cell {
+    FREE,
+    NUMBER(usize),
+    TILE(Tile),
+}
+
+impl Cell {
+    /// 判定や抽選で使う数字 (フリーマスは0、牌は牌の番号)
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use board::cell::{Cell, CellKind};
+    /// assert_eq!(Cell::FREE.number(), 0);
+    /// assert_eq!(Cell::NUMBER(15).number(), 15);
+    /// assert_eq!(CellKind::TILE.cell(28).number(), 28);
+    /// ```
+    pub fn number(self) -> usize {
+        match self {
+            Cell::FREE => 0,
+            Cell::NUMBER(number) => number,
+            Cell::TILE(tile) => tile.code(),
+        }
+    }
+
+    /// 値の種類 (フリーマスはどの種類の数字盤にもあるのでNone)
+    pub fn kind(self) -> Option<CellKind> {
+        match self {
+            Cell::FREE => None,
+            Cell::NUMBER(_) => Some(CellKind::NUMBER),
+            Cell::TILE(_) => Some(CellKind::TILE),
+        }
+    }
+}
+
+impl std::fmt::Display for Cell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Cell::FREE => write!(f, "FREE"),
+            Cell::NUMBER(number) => write!(f, "{number}"),
+            Cell::TILE(tile) => write!(f, "{tile}"),
+        }
+    }
+}
+
+impl Serialize for Cell {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Cell::FREE => serializer.serialize_u64(0),
+            Cell::NUMBER(number) => number.serialize(serializer),
+            Cell::TILE(tile) => tile.serialize(serializer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_serializes_cells() {
+        let cells: Vec<Cell> = [0, 1, 14, 34]
+            .into_iter()
+            .map(|n| CellKind::TILE.cell(n))
+            .collect();
+        assert_eq!(
+            serde_json::to_string(&cells).unwrap(),
+            r#"[0,"1m","5p","中"]"#
+        );
+
+        let cells: Vec<Cell> = [0, 1, 75]
+            .into_iter()
+            .map(|n| CellKind::NUMBER.cell(n))
+            .collect();
+        assert_eq!(serde_json::to_string(&cells).unwrap(), "[0,1,75]");
+    }
+}
@@ -0,0 +1,394 @@
+use crate::errors::Error;
+use crate::generate::{validate_distinct_numbers, NumberRange};
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 合計で振れるサイコロの最大数
+const MAX_SUM_DICE: usize = 8;
+/// 合計で使えるサイコロの最大の面の数
+const MAX_SUM_FACES: usize = 100;
+/// 桁を並べる場合に振れるサイコロの最大数
+const MAX_DIGIT_DICE: usize = 3;
+
+/// 出目から数字を作る方法
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DiceMode {
+    /// 出目の合計
+    SUM,
+    /// 出目を上の桁から順に並べる (10面のサイコロは0から9)
+    DIGITS,
+}
+
+/// 一緒に振るサイコロの組
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct DiceSet {
+    /// 各サイコロの面の数 (6なら1から6の目が出る)
+    pub dice: Vec<usize>,
+    pub mode: DiceMode,
+}
+
+impl Default for DiceSet {
+    /// 20面のサイコロ3つの合計 (3から60)
+    fn default() -> Self {
+        Self {
+            dice: vec![20; 3],
+            mode: DiceMode::SUM,
+        }
+    }
+}
+
+/// サイコロを振った結果
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Roll {
+    /// 各サイコロの出目 (`DiceSet::dice`の順)
+    pub faces: Vec<usize>,
+    /// 出目から作られた数字
+    pub number: usize,
+}
+
+impl DiceSet {
+    /// サイコロの組として正しいか検証する
+    pub fn validate(&self) -> Result<(), Error> {
+        let max_dice = match self.mode {
+            DiceMode::SUM => MAX_SUM_DICE,
+            DiceMode::DIGITS => MAX_DIGIT_DICE,
+        };
+        let max_faces = match self.mode {
+            DiceMode::SUM => MAX_SUM_FACES,
+            DiceMode::DIGITS => 10,
+        };
+
+        if self.dice.is_empty()
+            || self.dice.len() > max_dice
+            || self
+                .dice
+                .iter()
+                .any(|&faces| faces < 2 || faces > max_faces)
+        {
+            return Err(Error::InvalidDice);
+        }
+
+        Ok(())
+    }
+
+    /// sizeで与えられた大きさの数字盤を作れるか検証する
+    pub fn validate_for(&self, size: usize) -> Result<(), Error> {
+        self.validate()?;
+        validate_distinct_numbers(size, self.distribution().len())
+    }
+
+    /// 1つのサイコロで出る目
+    fn faces(&self, faces: usize) -> std::ops::RangeInclusive<usize> {
+        match (self.mode, faces) {
+            (DiceMode::DIGITS, 10) => 0..=9,
+            _ => 1..=faces,
+        }
+    }
+
+    /// 出目から数字を作る
+    fn number(&self, faces: &[usize]) -> usize {
+        match self.mode {
+            DiceMode::SUM => faces.iter().sum(),
+            DiceMode::DIGITS => faces.iter().fold(0, |number, face| number * 10 + face),
+        }
+    }
+
+    /// 出る可能性のある数字とその出方の数 (数字の昇順)
+    /// 0はフリーマスなので含まない
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use board::dice::{DiceMode, DiceSet};
+    /// let dice = DiceSet { dice: vec![6, 6], mode: DiceMode::SUM };
+    /// let distribution = dice.distribution();
+    /// assert_eq!(distribution.len(), 11);
+    /// assert_eq!(distribution[&7], 6);
+    /// assert_eq!(distribution[&12], 1);
+    /// ```
+    pub fn distribution(&self) -> BTreeMap<usize, u64> {
+        let mut ways = self.prefix_ways().pop().unwrap_or_default();
+        ways.remove(&0);
+        ways
+    }
+
+    /// 先頭からi個のサイコロで作られる数字とその出方の数 (i = 0..=サイコロの数)
+    fn prefix_ways(&self) -> Vec<BTreeMap<usize, u64>> {
+        let mut prefix = vec![BTreeMap::from([(0, 1)])];
+
+        for &faces in &self.dice {
+            let mut next = BTreeMap::new();
+            for (&number, &count) in &prefix[prefix.len() - 1] {
+                for face in self.faces(faces) {
+                    *next.entry(self.number(&[number, face])).or_default() += count;
+                }
+            }
+            prefix.push(next);
+        }
+
+        prefix
+    }
+
+    /// numberの最後の出目がfaceだった場合の、それより前の出目から作られた数字
+    fn previous(&self, number: usize, face: usize) -> Option<usize> {
+        let rest = number.checked_sub(face)?;
+        match self.mode {
+            DiceMode::SUM => Some(rest),
+            DiceMode::DIGITS => rest.is_multiple_of(10).then_some(rest / 10),
+        }
+    }
+
+    /// 出る数字を先に決めてから出目を選ぶための表
+    pub fn table(&self) -> RollTable {
+        RollTable {
+            dice: self.clone(),
+            prefix: self.prefix_ways(),
+        }
+    }
+
+    /// 出る可能性のある数字の範囲
+    pub fn range(&self) -> NumberRange {
+        let distribution = self.distribution();
+
+        NumberRange {
+            min: distribution.keys().next().copied().unwrap_or(1),
+            max: distribution.keys().last().copied().unwrap_or(1),
+        }
+    }
+
+    /// サイコロを振る
+    /// 全てのサイコロが0で数字が0になった場合は振り直す
+    pub fn roll(&self, rng: &mut impl Rng) -> Roll {
+        loop {
+            let faces: Vec<usize> = self
+                .dice
+                .iter()
+                .map(|&faces| rng.gen_range(self.faces(faces)))
+                .collect();
+            let number = self.number(&faces);
+
+            if number != 0 {
+                return Roll { faces, number };
+            }
+        }
+    }
+}
+
+/// 出る数字を先に決めてから、その数字になる出目を選ぶための表
+///
+/// 抽選済みの数字が出たら振り直す代わりに、残っている数字を出る確率で重み付けして選ぶ。
+/// 出る確率の低い数字だけが残っても振り直し続けることがない。
+#[derive(Clone, PartialEq, Debug)]
+pub struct RollTable {
+    dice: DiceSet,
+    /// 先頭からi個のサイコロで作られる数字とその出方の数
+    prefix: Vec<BTreeMap<usize, u64>>,
+}
+
+impl RollTable {
+    /// numberの出方の数 (出ない数字や0なら0)
+    pub fn ways(&self, number: usize) -> u64 {
+        match number {
+            0 => 0,
+            _ => self
+                .prefix
+                .last()
+                .and_then(|ways| ways.get(&number))
+                .copied()
+                .unwrap_or(0),
+        }
+    }
+
+    /// candidatesの数字が出るまで振った場合と同じ確率で出目を選ぶ
+    /// どの数字も出ない場合はNone
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use board::dice::{DiceMode, DiceSet};
+    /// let dice = DiceSet { dice: vec![6, 6], mode: DiceMode::SUM };
+    /// let mut rng = rand::thread_rng();
+    ///
+    /// let roll = dice.table().roll_among(&[2, 12], &mut rng).unwrap();
+    /// assert!(roll.faces == vec![1, 1] || roll.faces == vec![6, 6]);
+    /// assert_eq!(dice.table().roll_among(&[1, 13], &mut rng), None);
+    /// ```
+    pub fn roll_among(&self, candidates: &[usize], rng: &mut impl Rng) -> Option<Roll> {
+        let weights = candidates.iter().map(|&number| self.ways(number));
+        let index = WeightedIndex::new(weights).ok()?.sample(rng);
+
+        self.roll_number(candidates[index], rng)
+    }
+
+    /// numberになる出目の組をどれも同じ確率で選ぶ
+    /// numberが出ない場合はNone
+    pub fn roll_number(&self, number: usize, rng: &mut impl Rng) -> Option<Roll> {
+        if self.ways(number) == 0 {
+            return None;
+        }
+
+        // 最後のサイコロから順に、それより前のサイコロで残りの数字を作れる出方の数で重み付けして選ぶ
+        let mut faces = vec![0; self.dice.dice.len()];
+        let mut rest = number;
+        for (i, &sides) in self.dice.dice.iter().enumerate().rev() {
+            let choices: Vec<(usize, usize, u64)> = self
+                .dice
+                .faces(sides)
+                .filter_map(|face| {
+                    let previous = self.dice.previous(rest, face)?;
+                    let ways = *self.prefix[i].get(&previous)?;
+                    Some((face, previous, ways))
+                })
+                .collect();
+            let index = WeightedIndex::new(choices.iter().map(|&(_, _, ways)| ways))
+                .ok()?
+                .sample(rng);
+
+            (faces[i], rest) = (choices[index].0, choices[index].1);
+        }
+
+        Some(Roll { faces, number })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn it_combines_digits() {
+        let dice = DiceSet {
+            dice: vec![10, 6],
+            mode: DiceMode::DIGITS,
+        };
+        let distribution = dice.distribution();
+
+        // 十の位は0から9、一の位は1から6
+        assert_eq!(distribution.len(), 60);
+        assert_eq!(distribution.keys().next(), Some(&1));
+        assert_eq!(distribution.keys().last(), Some(&96));
+        assert!(!distribution.contains_key(&17));
+        assert!(distribution.values().all(|&count| count == 1));
+    }
+
+    #[test]
+    fn it_rolls_achievable_numbers() {
+        let dice = DiceSet::default();
+        let distribution = dice.distribution();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..1000 {
+            let roll = dice.roll(&mut rng);
+            assert_eq!(roll.faces.len(), 3);
+            assert_eq!(roll.number, roll.faces.iter().sum::<usize>());
+            assert!(distribution.contains_key(&roll.number));
+        }
+    }
+
+    #[test]
+    fn it_rolls_requested_number() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let dice = DiceSet {
+            dice: vec![100; 8],
+            mode: DiceMode::SUM,
+        };
+        let table = dice.table();
+        for number in [8, 9, 400, 799, 800] {
+            let roll = table.roll_number(number, &mut rng).unwrap();
+            assert_eq!(roll.faces.len(), 8);
+            assert_eq!(roll.faces.iter().sum::<usize>(), number);
+        }
+        assert_eq!(table.roll_number(7, &mut rng), None);
+        assert_eq!(table.roll_number(801, &mut rng), None);
+
+        let dice = DiceSet {
+            dice: vec![10, 6],
+            mode: DiceMode::DIGITS,
+        };
+        let table = dice.table();
+        assert_eq!(
+            table.roll_number(5, &mut rng),
+            Some(Roll {
+                faces: vec![0, 5],
+                number: 5
+            })
+        );
+        assert_eq!(table.roll_number(17, &mut rng), None);
+        assert_eq!(table.roll_number(0, &mut rng), None);
+    }
+
+    #[test]
+    fn it_rolls_among_candidates_by_likelihood() {
+        let dice = DiceSet {
+            dice: vec![6, 6],
+            mode: DiceMode::SUM,
+        };
+        let table = dice.table();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // 7は6通り、12は1通り
+        let sevens = (0..7000)
+            .filter(|_| table.roll_among(&[7, 12], &mut rng).unwrap().number == 7)
+            .count();
+        assert!((5700..6300).contains(&sevens), "{sevens}");
+        assert_eq!(table.roll_among(&[], &mut rng), None);
+    }
+
+    #[test]
+    fn it_never_rolls_zero() {
+        let dice = DiceSet {
+            dice: vec![10],
+            mode: DiceMode::DIGITS,
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..1000 {
+            assert_ne!(dice.roll(&mut rng).number, 0);
+        }
+    }
+
+    #[test]
+    fn it_rejects_invalid_dice() {
+        let invalid = [
+            DiceSet {
+                dice: vec![],
+                mode: DiceMode::SUM,
+            },
+            DiceSet {
+                dice: vec![1, 6],
+                mode: DiceMode::SUM,
+            },
+            DiceSet {
+                dice: vec![20, 6],
+                mode: DiceMode::DIGITS,
+            },
+            DiceSet {
+                dice: vec![10; 4],
+                mode: DiceMode::DIGITS,
+            },
+        ];
+
+        for dice in invalid {
+            assert_eq!(dice.validate(), Err(Error::InvalidDice));
+        }
+    }
+
+    #[test]
+    fn it_requires_enough_numbers_for_board() {
+        let dice = DiceSet {
+            dice: vec![6, 6],
+            mode: DiceMode::SUM,
+        };
+
+        assert_eq!(dice.validate_for(3), Ok(()));
+        assert_eq!(
+            dice.validate_for(5),
+            Err(Error::NotEnoughNumbers { size: 5, count: 11 })
+        );
+    }
+}
@@ -0,0 +1,35 @@
+#[derive(thiserror::Error, Clone, PartialEq, Debug)]
+pub enum Error {
+    #[error("Board size must not be zero")]
+    ZeroSize,
+
+    #[error("Board size {size} must be odd")]
+    EvenSize { size: usize },
+
+    #[error("Board size {size} is too small (at least {min})")]
+    SizeTooSmall { size: usize, min: usize },
+
+    #[error("Invalid number range {min}..={max}")]
+    InvalidRange { min: usize, max: usize },
+
+    #[error("Number range {min}..={max} is too large")]
+    RangeTooLarge { min: usize, max: usize },
+
+    #[error("Board size {size} is too large for number range {min}..={max}")]
+    SizeTooLarge { size: usize, min: usize, max: usize },
+
+    #[error("Pattern does not fit board size {size}")]
+    InvalidPattern { size: usize },
+
+    #[error("At least one winning pattern is required")]
+    NoPatterns,
+
+    #[error("Number {number} is out of range")]
+    InvalidNumber { number: usize },
+
+    #[error("Invalid dice set")]
+    InvalidDice,
+
+    #[error("Only {count} numbers are available, not enough for board size {size}")]
+    NotEnoughNumbers { size: usize, count: usize },
+}
@@ -1,7 +1,122 @@
 use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
 
+use crate::dice::DiceSet;
+use crate::errors::Error;
 use crate::matrix::Transpose;
+use crate::tile::{self, Tile};
+
+/// 標準の1列あたりに使われる数字の個数
+const NUMBERS_PER_COLUMN: usize = 15;
+
+/// 数字盤の最小のサイズ (1x1では中央のフリーマスだけになり、抽選前からビンゴになる)
+pub const MIN_SIZE: usize = 3;
+
+/// 数字盤に書ける最大の数字
+pub const MAX_NUMBER: usize = 999_999;
+
+/// 範囲に含められる数字の最大個数 (抽選機は範囲の数字を全て持つ)
+pub const MAX_RANGE_LEN: usize = 10_000;
+
+/// 数字盤に書かれうる数字の範囲 (min以上max以下)
+///
+/// 範囲は数字盤の列数で等分され、左の列から順に小さい数字が割り当てられる。
+/// 等分できなかった端数 (範囲の末尾) は使われない。
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct NumberRange {
+    pub min: usize,
+    pub max: usize,
+}
+
+impl NumberRange {
+    /// sizeで与えられた大きさの数字盤の標準の範囲 (1列あたり15個)
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use board::generate::NumberRange;
+    /// assert_eq!(NumberRange::for_size(5), NumberRange { min: 1, max: 75 });
+    /// assert_eq!(NumberRange::for_size(7), NumberRange { min: 1, max: 105 });
+    /// ```
+    pub fn for_size(size: usize) -> Self {
+        Self {
+            min: 1,
+            max: size * NUMBERS_PER_COLUMN,
+        }
+    }
+
+    /// 範囲に含まれる数字の個数 (max < minなら0)
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use board::generate::NumberRange;
+    /// assert_eq!(NumberRange { min: 1, max: 75 }.count(), 75);
+    /// assert_eq!(NumberRange { min: 2, max: 1 }.count(), 0);
+    /// assert_eq!(NumberRange { min: 0, max: usize::MAX }.count(), usize::MAX);
+    /// ```
+    pub fn count(&self) -> usize {
+        self.max
+            .checked_sub(self.min)
+            .map_or(0, |d| d.saturating_add(1))
+    }
+
+    /// sizeで与えられた大きさの数字盤で1列あたりに使われる数字の個数
+    pub fn per_column(&self, size: usize) -> usize {
+        self.count().checked_div(size).unwrap_or(0)
+    }
+
+    /// sizeで与えられた大きさの数字盤のcolumn列目に書かれうる数字の範囲
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use board::generate::NumberRange;
+    /// let range = NumberRange { min: 10, max: 40 };
+    /// assert_eq!(range.column(3, 0), 10..=19);
+    /// assert_eq!(range.column(3, 2), 30..=39);
+    /// ```
+    pub fn column(&self, size: usize, column: usize) -> RangeInclusive<usize> {
+        let per_column = self.per_column(size);
+        let min = self.min + column * per_column;
+
+        min..=min + per_column - 1
+    }
+
+    /// sizeで与えられた大きさの数字盤に実際に書かれうる数字の範囲
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use board::generate::NumberRange;
+    /// assert_eq!(NumberRange { min: 10, max: 40 }.usable(3), 10..=39);
+    /// ```
+    pub fn usable(&self, size: usize) -> RangeInclusive<usize> {
+        self.min..=self.min + self.per_column(size) * size - 1
+    }
+
+    /// sizeで与えられた大きさの数字盤を作れる範囲かどうか検証する
+    pub fn validate(&self, size: usize) -> Result<(), Error> {
+        let NumberRange { min, max } = *self;
+
+        validate_size(size)?;
+        // 0はフリーマスとして使われる
+        if min == 0 || max < min {
+            return Err(Error::InvalidRange { min, max });
+        }
+        if max > MAX_NUMBER || self.count() > MAX_RANGE_LEN {
+            return Err(Error::RangeTooLarge { min, max });
+        }
+        if self.per_column(size) < size {
+            return Err(Error::SizeTooLarge { size, min, max });
+        }
+
+        Ok(())
+    }
+}
 
 /// min以上max以下の数字をランダムに返す
 /// 実行する度に違う値が返ります
@@ -19,31 +134,37 @@ pub fn generate_number(rng: &mut StdRng, min: usize, max: usize) -> usize {
 }
 
 /// sizeで与えられた大きさの数字盤(二次元配列)を返す
+/// 各列にはrangeを列数で等分した範囲の数字が書かれる
 /// ```
-/// # use board::generate::generate_board_numbers;
+/// # use board::errors::Error;
+/// # use board::generate::{generate_board_numbers, NumberRange};
 /// let mut rng = rand::SeedableRng::seed_from_u64(0);
-/// assert_eq!(generate_board_numbers(&mut rng, 3), Ok(vec![vec![12, 19, 34], vec![1, 0, 42], vec![9, 27, 45]]));
-/// assert_eq!(generate_board_numbers(&mut rng, 2), Err("Board size must be odd".to_string()))
+/// assert_eq!(generate_board_numbers(&mut rng, 3, &NumberRange::for_size(3)), Ok(vec![vec![12, 19, 34], vec![1, 0, 42], vec![9, 27, 45]]));
+/// assert_eq!(generate_board_numbers(&mut rng, 2, &NumberRange::for_size(2)), Err(Error::EvenSize { size: 2 }))
 /// ```
-pub fn generate_board_numbers(rng: &mut StdRng, size: usize) -> Result<Vec<Vec<usize>>, String> {
-    if size % 2 == 0 {
-        return Err("Board size must be odd".to_string());
-    }
+pub fn generate_board_numbers(
+    rng: &mut StdRng,
+    size: usize,
+    range: &NumberRange,
+) -> Result<Vec<Vec<usize>>, Error> {
+    range.validate(size)?;
+
     let mut board: Vec<Vec<usize>> = vec![];
     for row in 0..size {
-        let min = row * 15 + 1;
-        let max = (row + 1) * 15;
+        let band = range.column(size, row);
+        let (min, max) = (*band.start(), *band.end());
         let mut column = vec![];
 
         while column.len() < size {
             // フリーマス
             if row == size / 2 && column.len() == size / 2 {
-                column.push(0)
+                column.push(0);
+                continue;
             }
 
             let gen_number = generate_number(rng, min, max);
 
-            if !column.iter().any(|&x| x == gen_number) {
+            if !column.contains(&gen_number) {
                 column.push(gen_number)
             }
         }
@@ -54,9 +175,112 @@ pub fn generate_board_numbers(rng: &mut StdRng, size: usize) -> Result<Vec<Vec<u
     Ok(board.into_iter().transpose().collect())
 }
 
+/// diceで出る可能性のある数字だけが書かれた数字盤(二次元配列)を返す
+/// 出やすい数字ほど選ばれやすい
+pub fn generate_dice_board_numbers(
+    rng: &mut StdRng,
+    size: usize,
+    dice: &DiceSet,
+) -> Result<Vec<Vec<usize>>, Error> {
+    dice.validate_for(size)?;
+
+    let candidates = dice.distribution().into_iter().collect();
+    generate_weighted_board_numbers(rng, size, candidates)
+}
+
+/// 麻雀牌の番号が書かれた数字盤(二次元配列)を返す
+/// 同じ牌は1度しか書かれないため、34種類に収まる大きさ (5x5まで) のみ作れる
+pub fn generate_tile_board_numbers(
+    rng: &mut StdRng,
+    size: usize,
+) -> Result<Vec<Vec<usize>>, Error> {
+    let candidates = Tile::all()
+        .map(|tile| (tile.code(), tile::COPIES as u64))
+        .collect();
+    generate_weighted_board_numbers(rng, size, candidates)
+}
+
+/// 数字盤のサイズが中央にフリーマスを置ける`MIN_SIZE`以上の奇数か検証する
+fn validate_size(size: usize) -> Result<(), Error> {
+    if size == 0 {
+        return Err(Error::ZeroSize);
+    }
+    if size.is_multiple_of(2) {
+        return Err(Error::EvenSize { size });
+    }
+    if size < MIN_SIZE {
+        return Err(Error::SizeTooSmall {
+            size,
+            min: MIN_SIZE,
+        });
+    }
+
+    Ok(())
+}
+
+/// count種類の数字からsizeで与えられた大きさの数字盤を作れるか検証する
+pub(crate) fn validate_distinct_numbers(size: usize, count: usize) -> Result<(), Error> {
+    validate_size(size)?;
+    if count < size * size - 1 {
+        return Err(Error::NotEnoughNumbers { size, count });
+    }
+
+    Ok(())
+}
+
+/// candidatesの数字を重み付きで重複なく選んだ数字盤(二次元配列)を返す
+/// 選ばれた数字は小さい順に左の列から割り当てられる
+fn generate_weighted_board_numbers(
+    rng: &mut StdRng,
+    size: usize,
+    mut candidates: Vec<(usize, u64)>,
+) -> Result<Vec<Vec<usize>>, Error> {
+    validate_distinct_numbers(size, candidates.len())?;
+
+    let mut chosen = vec![];
+    while chosen.len() < size * size - 1 {
+        let total: u64 = candidates.iter().map(|&(_, weight)| weight).sum();
+        let mut target = rng.gen_range(0..total);
+        let index = candidates
+            .iter()
+            .position(|&(_, weight)| match target.checked_sub(weight) {
+                Some(rest) => {
+                    target = rest;
+                    false
+                }
+                None => true,
+            })
+            .unwrap_or(candidates.len() - 1);
+
+        chosen.push(candidates.remove(index).0);
+    }
+    chosen.sort_unstable();
+
+    let mut chosen = chosen.into_iter();
+    let mut board: Vec<Vec<usize>> = vec![];
+    for row in 0..size {
+        let center = row == size / 2;
+        let mut column: Vec<usize> = chosen
+            .by_ref()
+            .take(if center { size - 1 } else { size })
+            .collect();
+        column.shuffle(rng);
+
+        // フリーマス
+        if center {
+            column.insert(size / 2, 0);
+        }
+
+        board.push(column)
+    }
+
+    Ok(board.into_iter().transpose().collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dice::DiceMode;
 
     #[test]
     fn it_can_generate_number() {
@@ -69,11 +293,11 @@ mod tests {
     fn it_can_generate_board_numbers() {
         let mut rng = rand::SeedableRng::seed_from_u64(0);
         assert_eq!(
-            generate_board_numbers(&mut rng, 3).unwrap(),
+            generate_board_numbers(&mut rng, 3, &NumberRange::for_size(3)).unwrap(),
             vec![[12, 19, 34], [1, 0, 42], [9, 27, 45]]
         );
         assert_eq!(
-            generate_board_numbers(&mut rng, 5).unwrap(),
+            generate_board_numbers(&mut rng, 5, &NumberRange::for_size(5)).unwrap(),
             vec![
                 [3, 22, 43, 53, 61],
                 [2, 29, 34, 59, 62],
@@ -83,7 +307,7 @@ mod tests {
             ]
         );
         assert_eq!(
-            generate_board_numbers(&mut rng, 7).unwrap(),
+            generate_board_numbers(&mut rng, 7, &NumberRange::for_size(7)).unwrap(),
             vec![
                 [12, 19, 32, 54, 61, 89, 93],
                 [14, 30, 33, 55, 74, 84, 98],
@@ -95,7 +319,7 @@ mod tests {
             ]
         );
         assert_eq!(
-            generate_board_numbers(&mut rng, 9).unwrap(),
+            generate_board_numbers(&mut rng, 9, &NumberRange::for_size(9)).unwrap(),
             vec![
                 [12, 29, 44, 58, 63, 90, 98, 114, 123],
                 [8, 19, 39, 47, 70, 85, 92, 106, 131],
@@ -114,8 +338,189 @@ mod tests {
     fn it_err_generate_board_numbers_when_even_number_given() {
         let mut rng = rand::SeedableRng::seed_from_u64(0);
         assert_eq!(
-            generate_board_numbers(&mut rng, 2),
-            Err("Board size must be odd".to_string())
+            generate_board_numbers(&mut rng, 2, &NumberRange::for_size(2)),
+            Err(Error::EvenSize { size: 2 })
         )
     }
+
+    #[test]
+    fn it_can_generate_board_numbers_in_custom_range() {
+        let mut rng = rand::SeedableRng::seed_from_u64(0);
+        let range = NumberRange { min: 101, max: 152 };
+        let board = generate_board_numbers(&mut rng, 5, &range).unwrap();
+
+        for row in &board {
+            for (column, number) in row.iter().enumerate() {
+                // 1列あたり10個ずつ、151と152は使われない
+                assert!(*number == 0 || range.column(5, column).contains(number));
+                assert!(*number <= 150);
+            }
+        }
+    }
+
+    #[test]
+    fn it_err_generate_board_numbers_when_size_is_too_small() {
+        let mut rng = rand::SeedableRng::seed_from_u64(0);
+        let too_small = Error::SizeTooSmall { size: 1, min: 3 };
+
+        assert_eq!(NumberRange::for_size(1).validate(1), Err(too_small.clone()));
+        assert_eq!(validate_distinct_numbers(1, 75), Err(too_small.clone()));
+        assert_eq!(
+            generate_board_numbers(&mut rng, 1, &NumberRange::for_size(1)),
+            Err(too_small)
+        );
+        assert!(generate_board_numbers(&mut rng, 3, &NumberRange::for_size(3)).is_ok());
+    }
+
+    #[test]
+    fn it_err_generate_board_numbers_when_range_is_too_small() {
+        let mut rng = rand::SeedableRng::seed_from_u64(0);
+        assert_eq!(
+            generate_board_numbers(&mut rng, 5, &NumberRange { min: 1, max: 24 }),
+            Err(Error::SizeTooLarge {
+                size: 5,
+                min: 1,
+                max: 24
+            })
+        );
+        assert_eq!(
+            generate_board_numbers(&mut rng, 5, &NumberRange { min: 0, max: 75 }),
+            Err(Error::InvalidRange { min: 0, max: 75 })
+        );
+        assert_eq!(
+            generate_board_numbers(&mut rng, 5, &NumberRange { min: 75, max: 1 }),
+            Err(Error::InvalidRange { min: 75, max: 1 })
+        );
+        assert_eq!(
+            generate_board_numbers(&mut rng, 0, &NumberRange::for_size(0)),
+            Err(Error::ZeroSize)
+        );
+    }
+
+    #[test]
+    fn it_err_validate_when_range_is_too_large() {
+        let largest = NumberRange {
+            min: MAX_NUMBER - MAX_RANGE_LEN + 1,
+            max: MAX_NUMBER,
+        };
+        assert_eq!(largest.validate(5), Ok(()));
+
+        let too_large = [
+            NumberRange {
+                min: 1,
+                max: MAX_RANGE_LEN + 1,
+            },
+            NumberRange {
+                min: MAX_NUMBER,
+                max: MAX_NUMBER + 1,
+            },
+            NumberRange {
+                min: 1,
+                max: usize::MAX,
+            },
+            NumberRange {
+                min: usize::MAX,
+                max: usize::MAX,
+            },
+        ];
+        for range in too_large {
+            assert_eq!(
+                range.validate(5),
+                Err(Error::RangeTooLarge {
+                    min: range.min,
+                    max: range.max
+                })
+            );
+        }
+        assert_eq!(
+            NumberRange {
+                min: 1,
+                max: usize::MAX
+            }
+            .per_column(5),
+            usize::MAX / 5
+        );
+    }
+
+    #[test]
+    fn it_generates_dice_board_from_achievable_numbers() {
+        let mut rng = rand::SeedableRng::seed_from_u64(0);
+        let dice = DiceSet {
+            dice: vec![10, 6],
+            mode: DiceMode::DIGITS,
+        };
+        let distribution = dice.distribution();
+        let board = generate_dice_board_numbers(&mut rng, 5, &dice).unwrap();
+
+        let mut numbers: Vec<usize> = board.iter().flatten().copied().collect();
+        assert_eq!(board[2][2], 0);
+        assert!(numbers
+            .iter()
+            .all(|number| *number == 0 || distribution.contains_key(number)));
+
+        // 左の列ほど小さい数字が書かれる
+        let columns: Vec<Vec<usize>> = board.clone().into_iter().transpose().collect();
+        for pair in columns.windows(2) {
+            let left = pair[0].iter().filter(|&&n| n != 0).max().unwrap();
+            let right = pair[1].iter().filter(|&&n| n != 0).min().unwrap();
+            assert!(left < right);
+        }
+
+        numbers.sort_unstable();
+        numbers.dedup();
+        assert_eq!(numbers.len(), 25);
+    }
+
+    #[test]
+    fn it_prefers_likely_dice_numbers() {
+        let mut rng = rand::SeedableRng::seed_from_u64(0);
+        let dice = DiceSet::default();
+        let (mut middle, mut edge) = (0, 0);
+
+        for _ in 0..200 {
+            let board = generate_dice_board_numbers(&mut rng, 5, &dice).unwrap();
+            for &number in board.iter().flatten() {
+                match number {
+                    // 3d20の合計で最も出やすい辺りと最も出にくい辺り
+                    30..=33 => middle += 1,
+                    3..=6 | 57..=60 => edge += 1,
+                    _ => {}
+                }
+            }
+        }
+
+        assert!(middle > edge * 2);
+    }
+
+    #[test]
+    fn it_err_generate_dice_board_when_dice_gives_too_few_numbers() {
+        let mut rng = rand::SeedableRng::seed_from_u64(0);
+        let dice = DiceSet {
+            dice: vec![6, 6],
+            mode: DiceMode::SUM,
+        };
+
+        assert_eq!(
+            generate_dice_board_numbers(&mut rng, 5, &dice),
+            Err(Error::NotEnoughNumbers { size: 5, count: 11 })
+        );
+        assert!(generate_dice_board_numbers(&mut rng, 3, &dice).is_ok());
+    }
+
+    #[test]
+    fn it_generates_tile_board_with_distinct_tiles() {
+        let mut rng = rand::SeedableRng::seed_from_u64(0);
+        let board = generate_tile_board_numbers(&mut rng, 5).unwrap();
+
+        let mut codes: Vec<usize> = board.iter().flatten().copied().collect();
+        assert!(codes.iter().all(|&code| code <= Tile::KINDS));
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), 25);
+
+        assert_eq!(
+            generate_tile_board_numbers(&mut rng, 7),
+            Err(Error::NotEnoughNumbers { size: 7, count: 34 })
+        );
+    }
 }
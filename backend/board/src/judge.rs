@@ -1,4 +1,6 @@
-use crate::{board::Board, matrix::Matrix};
+use crate::board::Board;
+use crate::mask::Line;
+use crate::pattern::Pattern;
 
 impl Board {
     /// ビンゴかどうか判定する
@@ -6,43 +8,19 @@ impl Board {
     /// # 例
     /// ```
     /// # use board::board::Board;
+    /// // [
+    /// //   [15, 18, 45],
+    /// //   [11, 0, 36],
+    /// //   [7, 19, 41],
+    /// // ]
     /// let mut board = Board::new(1, 3).unwrap();
-    /// board.numbers = vec![
-    ///   vec![15, 18, 45],
-    ///   vec![11, 0, 36],
-    ///   vec![7, 19, 41],
-    /// ];
-    /// board.opened = vec![15, 18, 45];
+    /// for number in [15, 18, 45] {
+    ///     board.open(number).unwrap();
+    /// }
     /// assert_eq!(board.judge_bingo(), Some(vec![vec![15, 18, 45]]));
     /// ```
-    pub fn judge_bingo(&mut self) -> Option<Vec<Vec<usize>>> {
-        let mut bingo = vec![];
-
-        for i in 0..self.size {
-            if opened_count_in_vec(&self.opened, self.numbers.row(i)) == self.size {
-                bingo.push(self.numbers.row(i));
-            }
-        }
-
-        for i in 0..self.size {
-            if opened_count_in_vec(&self.opened, self.numbers.col(i)) == self.size {
-                bingo.push(self.numbers.col(i));
-            }
-        }
-
-        if opened_count_in_vec(&self.opened, self.numbers.diagnoal_from_upper_left()) == self.size {
-            bingo.push(self.numbers.diagnoal_from_upper_left());
-        }
-
-        if opened_count_in_vec(&self.opened, self.numbers.diagnoal_from_upper_right()) == self.size
-        {
-            bingo.push(self.numbers.diagnoal_from_upper_right());
-        }
-
-        match bingo.len() {
-            1.. => Some(bingo),
-            _ => None,
-        }
+    pub fn judge_bingo(&self) -> Option<Vec<Vec<usize>>> {
+        self.judge(self.marks.bingo_lines())
     }
 
     /// リーチかどうか判定する
@@ -51,43 +29,86 @@ impl Board {
     /// ```
     /// # use board::board::Board;
     /// let mut board = Board::new(1, 3).unwrap();
-    /// board.numbers = vec![
-    ///   vec![15, 18, 45],
-    ///   vec![11, 0, 36],
-    ///   vec![7, 19, 41],
-    /// ];
-    /// board.opened = vec![15, 45];
+    /// for number in [15, 45] {
+    ///     board.open(number).unwrap();
+    /// }
     /// assert_eq!(board.judge_reach(), Some(vec![vec![15, 18, 45], vec![15, 0, 41], vec![45, 0, 7]]));
     /// ```
-    pub fn judge_reach(&mut self) -> Option<Vec<Vec<usize>>> {
-        let mut reach = vec![];
+    pub fn judge_reach(&self) -> Option<Vec<Vec<usize>>> {
+        self.judge(self.marks.reach_lines())
+    }
 
-        for i in 0..self.size {
-            if opened_count_in_vec(&self.opened, self.numbers.row(i)) == self.size - 1 {
-                reach.push(self.numbers.row(i))
+    /// 揃っているパターン (重複なし、`Board::patterns`の順)
+    ///
+    /// # 例
+    /// ```
+    /// # use board::board::Board;
+    /// # use board::generate::NumberRange;
+    /// # use board::pattern::Pattern;
+    /// let range = NumberRange::for_size(3);
+    /// let mut board = Board::with_patterns(1, 3, range, vec![Pattern::LINE, Pattern::CORNERS]).unwrap();
+    /// for number in [15, 45, 7, 41] {
+    ///     board.open(number).unwrap();
+    /// }
+    /// assert_eq!(board.judge_patterns(), vec![Pattern::LINE, Pattern::CORNERS]);
+    /// ```
+    pub fn judge_patterns(&self) -> Vec<Pattern> {
+        let mut patterns: Vec<Pattern> = vec![];
+        for line in self.marks.bingo_lines() {
+            if !patterns.contains(&line.pattern) {
+                patterns.push(line.pattern.clone());
             }
         }
 
-        for i in 0..self.size {
-            if opened_count_in_vec(&self.opened, self.numbers.col(i)) == self.size - 1 {
-                reach.push(self.numbers.col(i));
-            }
-        }
+        patterns
+    }
 
-        if opened_count_in_vec(&self.opened, self.numbers.diagnoal_from_upper_left())
-            == self.size - 1
-        {
-            reach.push(self.numbers.diagnoal_from_upper_left());
-        }
+    /// linesに書かれている数字を返す (ラインがない場合はNone)
+    fn judge<'a>(&self, lines: impl Iterator<Item = &'a Line>) -> Option<Vec<Vec<usize>>> {
+        let lines: Vec<Vec<usize>> = lines.map(|line| self.line_numbers(line)).collect();
 
-        if opened_count_in_vec(&self.opened, self.numbers.diagnoal_from_upper_right())
-            == self.size - 1
-        {
-            reach.push(self.numbers.diagnoal_from_upper_right());
+        match lines.len() {
+            1.. => Some(lines),
+            _ => None,
         }
+    }
+}
+
+/// `Matrix`で各ラインを作り直し、開けた数字を線形に探す素朴な判定
+///
+/// `Board`はビットマスクで判定するため、その検証とベンチマークの比較対象として残している。
+pub mod naive {
+    use super::opened_count_in_vec;
+    use crate::matrix::Matrix;
+
+    /// # 例
+    /// ```
+    /// # use board::judge::naive;
+    /// let numbers = vec![vec![15, 18, 45], vec![11, 0, 36], vec![7, 19, 41]];
+    /// assert_eq!(naive::judge_bingo(&numbers, &[15, 18, 45]), Some(vec![vec![15, 18, 45]]));
+    /// ```
+    pub fn judge_bingo(numbers: &[Vec<usize>], opened: &[usize]) -> Option<Vec<Vec<usize>>> {
+        judge(numbers, opened, numbers.len())
+    }
+
+    pub fn judge_reach(numbers: &[Vec<usize>], opened: &[usize]) -> Option<Vec<Vec<usize>>> {
+        judge(numbers, opened, numbers.len() - 1)
+    }
+
+    fn judge(numbers: &[Vec<usize>], opened: &[usize], count: usize) -> Option<Vec<Vec<usize>>> {
+        let size = numbers.len();
+        let lines: Vec<Vec<usize>> = (0..size)
+            .map(|i| numbers.row(i))
+            .chain((0..size).map(|i| numbers.col(i)))
+            .chain([
+                numbers.diagnoal_from_upper_left(),
+                numbers.diagnoal_from_upper_right(),
+            ])
+            .filter(|line| opened_count_in_vec(opened, line.clone()) == count)
+            .collect();
 
-        match reach.len() {
-            1.. => Some(reach),
+        match lines.len() {
+            1.. => Some(lines),
             _ => None,
         }
     }
@@ -116,8 +137,8 @@ mod tests {
         //   [7, 19, 41],
         // ]
         let mut board = Board::new(1, 3).unwrap();
-        board.open(15);
-        board.open(45);
+        board.open(15).unwrap();
+        board.open(45).unwrap();
 
         // X = 開けたマス
         // [
@@ -130,7 +151,7 @@ mod tests {
             Some(vec![vec![15, 18, 45], vec![15, 0, 41], vec![45, 0, 7]])
         );
 
-        board.open(11);
+        board.open(11).unwrap();
 
         // X = 開けたマス
         // [
@@ -158,34 +179,62 @@ mod tests {
         //   [7, 19, 41],
         // ]
         let mut board = Board::new(1, 3).unwrap();
-        board.open(15);
-        board.open(45);
-        board.open(18);
+        board.open(15).unwrap();
+        board.open(45).unwrap();
+        board.open(18).unwrap();
         assert_eq!(board.judge_bingo(), Some(vec![vec![15, 18, 45]]));
     }
 
     #[test]
     fn works_judge_bingo_col() {
         let mut board = Board::new(1, 3).unwrap();
-        board.open(15);
-        board.open(11);
-        board.open(7);
+        board.open(15).unwrap();
+        board.open(11).unwrap();
+        board.open(7).unwrap();
         assert_eq!(board.judge_bingo(), Some(vec![vec![15, 11, 7]]));
     }
 
     #[test]
     fn works_judge_bingo_diagnoal_from_upper_left() {
         let mut board = Board::new(1, 3).unwrap();
-        board.open(15);
-        board.open(41);
+        board.open(15).unwrap();
+        board.open(41).unwrap();
         assert_eq!(board.judge_bingo(), Some(vec![vec![15, 0, 41]]));
     }
 
+    #[test]
+    fn it_agrees_with_naive_judge() {
+        use crate::generate::NumberRange;
+        use rand::rngs::StdRng;
+        use rand::seq::SliceRandom;
+        use rand::SeedableRng;
+
+        for size in [3, 5, 7, 9, 11, 13, 15] {
+            for id in 0..4 {
+                let mut board = Board::new(id, size).unwrap();
+                let mut numbers: Vec<usize> = NumberRange::for_size(size).usable(size).collect();
+                numbers.shuffle(&mut StdRng::seed_from_u64(id));
+
+                for number in numbers {
+                    board.open(number).unwrap();
+                    assert_eq!(
+                        board.judge_bingo(),
+                        naive::judge_bingo(&board.numbers(), &board.opened())
+                    );
+                    assert_eq!(
+                        board.judge_reach(),
+                        naive::judge_reach(&board.numbers(), &board.opened())
+                    );
+                }
+            }
+        }
+    }
+
     #[test]
     fn works_judge_bingo_diagnoal_from_upper_right() {
         let mut board = Board::new(1, 3).unwrap();
-        board.open(45);
-        board.open(7);
+        board.open(45).unwrap();
+        board.open(7).unwrap();
         assert_eq!(board.judge_bingo(), Some(vec![vec![45, 0, 7]]));
     }
 }
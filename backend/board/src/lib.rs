@@ -1,4 +1,11 @@
+pub mod analysis;
 pub mod board;
+pub mod cell;
+pub mod dice;
+pub mod errors;
 pub mod generate;
 pub mod judge;
+pub mod mask;
 pub mod matrix;
+pub mod pattern;
+pub mod tile;
@@ -0,0 +1,313 @@
+use crate::cell::Cell;
+use crate::pattern::Pattern;
+use std::collections::{BTreeSet, HashMap};
+
+/// 1マスを1bitで表したマスの集合
+///
+/// マスは左上から右に向かって`行 * size + 列`の番号で表す。
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct Bitset {
+    words: Vec<u64>,
+}
+
+impl Bitset {
+    /// len個のマスを入れられる空の集合
+    pub fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    pub fn insert(&mut self, cell: usize) {
+        self.words[cell / 64] |= 1 << (cell % 64);
+    }
+
+    pub fn contains(&self, cell: usize) -> bool {
+        self.words[cell / 64] & (1 << (cell % 64)) != 0
+    }
+
+    /// otherと共通するマスの数
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use board::mask::Bitset;
+    /// let mut a = Bitset::new(100);
+    /// let mut b = Bitset::new(100);
+    /// for cell in [0, 64, 99] {
+    ///     a.insert(cell);
+    /// }
+    /// for cell in [64, 99, 50] {
+    ///     b.insert(cell);
+    /// }
+    /// assert_eq!(a.intersection_count(&b), 2);
+    /// ```
+    pub fn intersection_count(&self, other: &Bitset) -> usize {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .map(|(a, b)| (a & b).count_ones() as usize)
+            .sum()
+    }
+}
+
+/// 数字盤の1ライン (パターンの1つの形)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Line {
+    /// このラインが属するパターン
+    pub pattern: Pattern,
+    /// ラインに含まれるマス (並び順のまま)
+    pub cells: Vec<usize>,
+    /// ラインに含まれるマスの集合
+    pub mask: Bitset,
+}
+
+impl Line {
+    fn new(size: usize, pattern: Pattern, cells: Vec<usize>) -> Self {
+        let mut mask = Bitset::new(size * size);
+        for &cell in &cells {
+            mask.insert(cell);
+        }
+
+        Self {
+            pattern,
+            cells,
+            mask,
+        }
+    }
+}
+
+/// sizeで与えられた大きさの数字盤のラインとマスの対応表
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LineIndex {
+    /// 全ライン (パターンの順、各パターン内は`Pattern::shapes`の順)
+    pub lines: Vec<Line>,
+    /// 各マスを通るラインの番号 (昇順)
+    pub cell_lines: Vec<Vec<usize>>,
+}
+
+impl LineIndex {
+    /// # 例
+    ///
+    /// ```
+    /// # use board::mask::LineIndex;
+    /// # use board::pattern::Pattern;
+    /// let index = LineIndex::new(3, &[Pattern::LINE]);
+    /// assert_eq!(index.lines.len(), 8);
+    /// // 中央のマスは行、列、対角線2本の計4ラインに含まれる
+    /// assert_eq!(index.cell_lines[4], vec![1, 4, 6, 7]);
+    /// ```
+    pub fn new(size: usize, patterns: &[Pattern]) -> Self {
+        let lines: Vec<Line> = patterns
+            .iter()
+            .flat_map(|pattern| {
+                pattern
+                    .shapes(size)
+                    .into_iter()
+                    .map(|cells| Line::new(size, pattern.clone(), cells))
+            })
+            .collect();
+
+        let mut cell_lines = vec![vec![]; size * size];
+        for (i, line) in lines.iter().enumerate() {
+            for &cell in &line.cells {
+                cell_lines[cell].push(i);
+            }
+        }
+
+        Self { lines, cell_lines }
+    }
+}
+
+/// 数字盤のどのマスが開いているかを保持する
+///
+/// 各ラインの開いていないマスの数を持ち、開けたマスを通るラインだけを更新する。
+/// 揃っているラインとリーチのラインは全ラインを調べ直さずに引ける。
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Marks {
+    index: LineIndex,
+    /// 値から値が書かれたマスへの対応
+    positions: HashMap<Cell, usize>,
+    /// 開いているマス (フリーマスを含む)
+    marked: Bitset,
+    /// 各ラインのうち開いていないマスの数 (ラインの番号順)
+    missing: Vec<usize>,
+    /// 揃っているラインの番号
+    bingo: BTreeSet<usize>,
+    /// あと1マスで揃うラインの番号
+    reach: BTreeSet<usize>,
+}
+
+impl Marks {
+    pub fn new(cells: &[Vec<Cell>], patterns: &[Pattern]) -> Self {
+        let size = cells.len();
+        let mut positions = HashMap::new();
+        let mut marked = Bitset::new(size * size);
+
+        for (cell, &value) in cells.iter().flatten().enumerate() {
+            match value {
+                // フリーマスは最初から開いている
+                Cell::FREE => marked.insert(cell),
+                _ => {
+                    positions.insert(value, cell);
+                }
+            }
+        }
+
+        let index = LineIndex::new(size, patterns);
+        let missing: Vec<usize> = index
+            .lines
+            .iter()
+            .map(|line| line.cells.len() - marked.intersection_count(&line.mask))
+            .collect();
+        let lines_with = |count: usize| {
+            (0..missing.len())
+                .filter(|&i| missing[i] == count)
+                .collect()
+        };
+        let (bingo, reach) = (lines_with(0), lines_with(1));
+
+        Self {
+            index,
+            positions,
+            marked,
+            missing,
+            bingo,
+            reach,
+        }
+    }
+
+    /// 値が書かれたマス
+    pub fn position(&self, value: Cell) -> Option<usize> {
+        self.positions.get(&value).copied()
+    }
+
+    pub fn is_marked(&self, cell: usize) -> bool {
+        self.marked.contains(cell)
+    }
+
+    /// マスを開け、マスを通るラインの開いていないマスの数を更新する
+    pub fn mark(&mut self, cell: usize) {
+        if self.marked.contains(cell) {
+            return;
+        }
+        self.marked.insert(cell);
+
+        for &i in &self.index.cell_lines[cell] {
+            self.missing[i] -= 1;
+            match self.missing[i] {
+                0 => {
+                    self.reach.remove(&i);
+                    self.bingo.insert(i);
+                }
+                1 => {
+                    self.reach.insert(i);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub fn lines(&self) -> &[Line] {
+        &self.index.lines
+    }
+
+    /// cellを通るラインの番号
+    pub fn lines_through(&self, cell: usize) -> &[usize] {
+        &self.index.cell_lines[cell]
+    }
+
+    /// i番目のラインのうち開いていないマスの数
+    pub fn missing_count(&self, i: usize) -> usize {
+        self.missing[i]
+    }
+
+    /// 揃っているライン (ラインの番号順)
+    pub fn bingo_lines(&self) -> impl Iterator<Item = &Line> {
+        self.bingo.iter().map(|&i| &self.index.lines[i])
+    }
+
+    /// あと1マスで揃うライン (ラインの番号順)
+    pub fn reach_lines(&self) -> impl Iterator<Item = &Line> {
+        self.reach.iter().map(|&i| &self.index.lines[i])
+    }
+
+    /// あと1マスで揃うラインの数
+    pub fn reach_count(&self) -> usize {
+        self.reach.len()
+    }
+
+    /// 開いていないマスがちょうどmissing個のライン
+    pub fn lines_missing(&self, missing: usize) -> impl Iterator<Item = &Line> {
+        self.index
+            .lines
+            .iter()
+            .zip(&self.missing)
+            .filter(move |(_, &count)| count == missing)
+            .map(|(line, _)| line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::CellKind;
+
+    #[test]
+    fn it_indexes_lines_through_cell() {
+        let index = LineIndex::new(3, &[Pattern::LINE, Pattern::CORNERS]);
+
+        assert_eq!(index.lines.len(), 9);
+        assert_eq!(index.cell_lines[0], vec![0, 3, 6, 8]);
+        assert_eq!(index.cell_lines[1], vec![0, 4]);
+    }
+
+    #[test]
+    fn it_handles_boards_larger_than_a_word() {
+        let index = LineIndex::new(15, &[Pattern::LINE]);
+        let last = index.lines.last().unwrap();
+
+        assert_eq!(last.cells[14], 14 * 15);
+        assert!(last.mask.contains(14 * 15));
+        assert_eq!(last.mask.intersection_count(&last.mask), 15);
+    }
+
+    #[test]
+    fn it_marks_free_cell_from_start() {
+        let cells: Vec<Vec<Cell>> = [[15, 18, 45], [11, 0, 36], [7, 19, 41]]
+            .iter()
+            .map(|row| row.iter().map(|&n| CellKind::NUMBER.cell(n)).collect())
+            .collect();
+        let marks = Marks::new(&cells, &[Pattern::LINE]);
+
+        assert!(marks.is_marked(4));
+        assert_eq!(marks.position(Cell::NUMBER(36)), Some(5));
+        assert_eq!(marks.position(Cell::FREE), None);
+        assert_eq!(marks.lines_missing(2).count(), 4);
+    }
+
+    #[test]
+    fn it_updates_only_lines_through_marked_cell() {
+        let cells: Vec<Vec<Cell>> = [[15, 18, 45], [11, 0, 36], [7, 19, 41]]
+            .iter()
+            .map(|row| row.iter().map(|&n| CellKind::NUMBER.cell(n)).collect())
+            .collect();
+        let mut marks = Marks::new(&cells, &[Pattern::LINE]);
+
+        marks.mark(0);
+        assert_eq!(marks.reach_count(), 1);
+        marks.mark(2);
+        assert_eq!(marks.reach_count(), 3);
+        marks.mark(1);
+        assert_eq!(marks.bingo_lines().count(), 1);
+        assert_eq!(marks.reach_count(), 3);
+
+        // 同じマスを開け直しても数は変わらない
+        marks.mark(1);
+        assert_eq!(marks.missing_count(0), 0);
+        for (i, line) in marks.lines().iter().enumerate() {
+            let marked = line.cells.iter().filter(|&&c| marks.is_marked(c)).count();
+            assert_eq!(marks.missing_count(i), line.cells.len() - marked);
+        }
+    }
+}
@@ -60,7 +60,7 @@ pub trait Matrix {
     fn diagnoal_from_upper_right(&self) -> Vec<usize>;
 }
 
-impl Matrix for Vec<Vec<usize>> {
+impl Matrix for [Vec<usize>] {
     fn row(&self, row: usize) -> Vec<usize> {
         self.iter().enumerate().map(|(i, _)| self[row][i]).collect()
     }
@@ -97,7 +97,7 @@ mod tests {
 
     #[test]
     fn it_works_matrix_row() {
-        let matrix = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let matrix = [vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
         assert_eq!(matrix.row(0), vec![1, 2, 3]);
         assert_eq!(matrix.row(1), vec![4, 5, 6]);
         assert_eq!(matrix.row(2), vec![7, 8, 9]);
@@ -105,7 +105,7 @@ mod tests {
 
     #[test]
     fn it_works_matrix_col() {
-        let matrix = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let matrix = [vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
         assert_eq!(matrix.col(0), vec![1, 4, 7]);
         assert_eq!(matrix.col(1), vec![2, 5, 8]);
         assert_eq!(matrix.col(2), vec![3, 6, 9]);
@@ -113,13 +113,13 @@ mod tests {
 
     #[test]
     fn it_works_matrix_diagnoal_from_upper_left() {
-        let matrix = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let matrix = [vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
         assert_eq!(matrix.diagnoal_from_upper_left(), vec![1, 5, 9]);
     }
 
     #[test]
     fn it_works_matrix_diagnoal_from_upper_right() {
-        let matrix = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let matrix = [vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
         assert_eq!(matrix.diagnoal_from_upper_right(), vec![3, 5, 7]);
     }
 }
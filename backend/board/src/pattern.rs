@@ -0,0 +1,157 @@
+use crate::errors::Error;
+use serde::{Deserialize, Serialize};
+
+/// ビンゴと認められるマスの並び
+///
+/// `LINE`以外は1つの形で、その形のマスが全て開くとビンゴになる。
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub enum Pattern {
+    /// 行、列、対角線のいずれか1ライン
+    LINE,
+    /// 四隅
+    CORNERS,
+    /// 全マス
+    BLACKOUT,
+    /// 外周
+    FRAME,
+    /// 2本の対角線
+    X,
+    /// 中央の行と列
+    PLUS,
+    /// 左端の列と下端の行
+    L,
+    /// 任意のマス (trueのマスが全て開くとビンゴ)
+    CUSTOM { cells: Vec<Vec<bool>> },
+}
+
+impl Pattern {
+    /// sizeで与えられた大きさの数字盤で使えるパターンかどうか検証する
+    pub fn validate(&self, size: usize) -> Result<(), Error> {
+        if let Pattern::CUSTOM { cells } = self {
+            if cells.len() != size
+                || cells.iter().any(|row| row.len() != size)
+                || !cells.iter().flatten().any(|&cell| cell)
+            {
+                return Err(Error::InvalidPattern { size });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// sizeで与えられた大きさの数字盤でこのパターンになるマスの組
+    ///
+    /// マスは左上から右に向かって`行 * size + 列`の番号で表す。
+    /// `LINE`は行、列、左上からの対角線、右上からの対角線の順に並んだライン毎の組になる。
+    /// それ以外は1つの組で、マスは番号順に並ぶ。
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use board::pattern::Pattern;
+    /// assert_eq!(Pattern::CORNERS.shapes(3), vec![vec![0, 2, 6, 8]]);
+    /// assert_eq!(Pattern::PLUS.shapes(3), vec![vec![1, 3, 4, 5, 7]]);
+    /// assert_eq!(Pattern::LINE.shapes(3).len(), 8);
+    /// ```
+    pub fn shapes(&self, size: usize) -> Vec<Vec<usize>> {
+        let last = size - 1;
+        let center = size / 2;
+
+        match self {
+            Pattern::LINE => {
+                let mut lines: Vec<Vec<usize>> = vec![];
+                for row in 0..size {
+                    lines.push((0..size).map(|col| row * size + col).collect());
+                }
+                for col in 0..size {
+                    lines.push((0..size).map(|row| row * size + col).collect());
+                }
+                lines.push((0..size).map(|i| i * size + i).collect());
+                lines.push((0..size).map(|i| i * size + last - i).collect());
+
+                lines
+            }
+            _ => vec![shape(size, |row, col| match self {
+                Pattern::CORNERS => (row == 0 || row == last) && (col == 0 || col == last),
+                Pattern::BLACKOUT => true,
+                Pattern::FRAME => row == 0 || row == last || col == 0 || col == last,
+                Pattern::X => row == col || row + col == last,
+                Pattern::PLUS => row == center || col == center,
+                Pattern::L => col == 0 || row == last,
+                Pattern::CUSTOM { cells } => cells[row][col],
+                Pattern::LINE => unreachable!(),
+            })],
+        }
+    }
+}
+
+/// containsがtrueを返すマスの組
+fn shape(size: usize, contains: impl Fn(usize, usize) -> bool) -> Vec<usize> {
+    (0..size * size)
+        .filter(|cell| contains(cell / size, cell % size))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_builds_shapes_of_5x5() {
+        assert_eq!(Pattern::CORNERS.shapes(5), vec![vec![0, 4, 20, 24]]);
+        assert_eq!(Pattern::BLACKOUT.shapes(5)[0].len(), 25);
+        assert_eq!(Pattern::FRAME.shapes(5)[0].len(), 16);
+        assert_eq!(
+            Pattern::X.shapes(5),
+            vec![vec![0, 4, 6, 8, 12, 16, 18, 20, 24]]
+        );
+        assert_eq!(
+            Pattern::L.shapes(5),
+            vec![vec![0, 5, 10, 15, 20, 21, 22, 23, 24]]
+        );
+    }
+
+    #[test]
+    fn it_builds_lines_in_order() {
+        assert_eq!(
+            Pattern::LINE.shapes(3),
+            vec![
+                vec![0, 1, 2],
+                vec![3, 4, 5],
+                vec![6, 7, 8],
+                vec![0, 3, 6],
+                vec![1, 4, 7],
+                vec![2, 5, 8],
+                vec![0, 4, 8],
+                vec![2, 4, 6],
+            ]
+        );
+    }
+
+    #[test]
+    fn it_builds_custom_shape() {
+        let pattern = Pattern::CUSTOM {
+            cells: vec![
+                vec![false, true, false],
+                vec![true, false, true],
+                vec![false, true, false],
+            ],
+        };
+
+        assert_eq!(pattern.validate(3), Ok(()));
+        assert_eq!(pattern.shapes(3), vec![vec![1, 3, 5, 7]]);
+    }
+
+    #[test]
+    fn it_rejects_invalid_custom_pattern() {
+        let pattern = Pattern::CUSTOM {
+            cells: vec![vec![true; 3]; 3],
+        };
+        assert_eq!(pattern.validate(5), Err(Error::InvalidPattern { size: 5 }));
+
+        let empty = Pattern::CUSTOM {
+            cells: vec![vec![false; 3]; 3],
+        };
+        assert_eq!(empty.validate(3), Err(Error::InvalidPattern { size: 3 }));
+    }
+}
@@ -0,0 +1,241 @@
+use crate::errors::Error;
+use crate::generate::validate_distinct_numbers;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// 1種類の牌の枚数
+pub const COPIES: usize = 4;
+
+/// 数牌の種類
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Suit {
+    /// 萬子 (m)
+    MANZU,
+    /// 筒子 (p)
+    PINZU,
+    /// 索子 (s)
+    SOUZU,
+}
+
+impl Suit {
+    const ALL: [Suit; 3] = [Suit::MANZU, Suit::PINZU, Suit::SOUZU];
+
+    fn notation(self) -> char {
+        match self {
+            Suit::MANZU => 'm',
+            Suit::PINZU => 'p',
+            Suit::SOUZU => 's',
+        }
+    }
+}
+
+/// 字牌
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Honor {
+    /// 東
+    EAST,
+    /// 南
+    SOUTH,
+    /// 西
+    WEST,
+    /// 北
+    NORTH,
+    /// 白
+    WHITE,
+    /// 發
+    GREEN,
+    /// 中
+    RED,
+}
+
+impl Honor {
+    const ALL: [Honor; 7] = [
+        Honor::EAST,
+        Honor::SOUTH,
+        Honor::WEST,
+        Honor::NORTH,
+        Honor::WHITE,
+        Honor::GREEN,
+        Honor::RED,
+    ];
+
+    fn notation(self) -> char {
+        match self {
+            Honor::EAST => '東',
+            Honor::SOUTH => '南',
+            Honor::WEST => '西',
+            Honor::NORTH => '北',
+            Honor::WHITE => '白',
+            Honor::GREEN => '發',
+            Honor::RED => '中',
+        }
+    }
+}
+
+/// 麻雀牌の種類 (34種類)
+///
+/// APIでは`1m`や`東`のような表記で表す。
+/// 数字盤や抽選では牌の番号 (`Tile::code`) を数字として扱う。
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Tile {
+    /// 数牌 (rankは1から9)
+    SUITED {
+        suit: Suit,
+        rank: usize,
+    },
+    HONOR(Honor),
+}
+
+impl Tile {
+    /// 牌の種類の数
+    pub const KINDS: usize = 34;
+
+    /// 全種類の牌 (番号順)
+    pub fn all() -> impl Iterator<Item = Tile> {
+        (1..=Self::KINDS).filter_map(Self::from_code)
+    }
+
+    /// 牌の番号 (萬子、筒子、索子、字牌の順に1から34)
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use board::tile::Tile;
+    /// let tile: Tile = "5p".parse().unwrap();
+    /// assert_eq!(tile.code(), 14);
+    /// assert_eq!(Tile::from_code(14), Some(tile));
+    /// assert_eq!("中".parse::<Tile>().unwrap().code(), 34);
+    /// ```
+    pub fn code(self) -> usize {
+        match self {
+            Tile::SUITED { suit, rank } => {
+                let index = Suit::ALL.iter().position(|&s| s == suit).unwrap_or(0);
+                index * 9 + rank
+            }
+            Tile::HONOR(honor) => 28 + Honor::ALL.iter().position(|&h| h == honor).unwrap_or(0),
+        }
+    }
+
+    /// sizeで与えられた大きさの牌の数字盤を作れるか検証する
+    /// 同じ牌は1度しか書かれないため5x5までしか作れない
+    pub fn validate_board(size: usize) -> Result<(), Error> {
+        validate_distinct_numbers(size, Self::KINDS)
+    }
+
+    /// 牌の番号から牌を返す
+    pub fn from_code(code: usize) -> Option<Tile> {
+        match code {
+            1..=27 => Some(Tile::SUITED {
+                suit: Suit::ALL[(code - 1) / 9],
+                rank: (code - 1) % 9 + 1,
+            }),
+            28..=34 => Some(Tile::HONOR(Honor::ALL[code - 28])),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Tile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tile::SUITED { suit, rank } => write!(f, "{}{}", rank, suit.notation()),
+            Tile::HONOR(honor) => write!(f, "{}", honor.notation()),
+        }
+    }
+}
+
+/// 牌の表記として読めなかった
+#[derive(thiserror::Error, Clone, PartialEq, Debug)]
+#[error("Invalid tile notation {0}")]
+pub struct ParseTileError(String);
+
+impl FromStr for Tile {
+    type Err = ParseTileError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseTileError(s.to_string());
+
+        let mut chars = s.chars();
+        let (first, second) = (chars.next(), chars.next());
+        if chars.next().is_some() {
+            return Err(error());
+        }
+
+        match (first, second) {
+            (Some(c), None) => Honor::ALL
+                .into_iter()
+                .find(|honor| honor.notation() == c)
+                .map(Tile::HONOR)
+                .ok_or_else(error),
+            (Some(rank @ '1'..='9'), Some(notation)) => Suit::ALL
+                .into_iter()
+                .find(|suit| suit.notation() == notation)
+                .map(|suit| Tile::SUITED {
+                    suit,
+                    rank: rank as usize - '0' as usize,
+                })
+                .ok_or_else(error),
+            _ => Err(error()),
+        }
+    }
+}
+
+impl Serialize for Tile {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Tile {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_round_trips_every_tile() {
+        let tiles: Vec<Tile> = Tile::all().collect();
+        assert_eq!(tiles.len(), Tile::KINDS);
+
+        for (i, tile) in tiles.into_iter().enumerate() {
+            assert_eq!(tile.code(), i + 1);
+            assert_eq!(tile.to_string().parse(), Ok(tile));
+        }
+    }
+
+    #[test]
+    fn it_writes_tile_notation() {
+        let notations: Vec<String> = Tile::all().map(|tile| tile.to_string()).collect();
+
+        assert_eq!(notations[0], "1m");
+        assert_eq!(notations[17], "9p");
+        assert_eq!(notations[18], "1s");
+        assert_eq!(notations[27..], ["東", "南", "西", "北", "白", "發", "中"]);
+    }
+
+    #[test]
+    fn it_rejects_invalid_notation() {
+        for notation in ["", "0m", "10m", "5z", "m", "東南", "5"] {
+            assert!(notation.parse::<Tile>().is_err(), "{notation}");
+        }
+    }
+
+    #[test]
+    fn it_serializes_as_notation() {
+        let tile = Tile::SUITED {
+            suit: Suit::SOUZU,
+            rank: 7,
+        };
+
+        assert_eq!(serde_json::to_string(&tile).unwrap(), r#""7s""#);
+        assert_eq!(serde_json::from_str::<Tile>(r#""7s""#).unwrap(), tile);
+        assert!(serde_json::from_str::<Tile>(r#""7x""#).is_err());
+    }
+}
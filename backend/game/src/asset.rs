@@ -0,0 +1,251 @@
+use crate::config::GameMode;
+use crate::errors::Error;
+use board::generate::NumberRange;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// 数字に割り当てる素材
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Asset {
+    /// 素材のID (マニフェスト内で一意)
+    pub id: String,
+    /// 表示名 (著名人の名前やフォント名)
+    pub name: String,
+    /// 肖像画像のパス (著名人モード)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    /// 数字を描くフォントファミリー (フォントモード)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+}
+
+/// マニフェストを読み込めなかった
+#[derive(thiserror::Error, Debug)]
+pub enum AssetError {
+    #[error("Failed to read asset manifest: {0}")]
+    Read(#[from] std::io::Error),
+
+    #[error("Failed to parse asset manifest: {0}")]
+    Parse(#[from] serde_json::Error),
+
+    #[error("Asset ID {id} is duplicated")]
+    DuplicateId { id: String },
+
+    #[error("Celebrity {id} has no image")]
+    MissingImage { id: String },
+
+    #[error("Font {id} has no family")]
+    MissingFamily { id: String },
+}
+
+/// 著名人モードとフォントモードで使う素材の一覧
+///
+/// 起動時にJSONファイルから読み込む。
+///
+/// ```json
+/// {
+///   "celebrities": [{ "id": "einstein", "name": "Albert Einstein", "image": "celebrity/einstein.png" }],
+///   "fonts": [{ "id": "noto-serif", "name": "Noto Serif", "family": "Noto Serif JP" }]
+/// }
+/// ```
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+#[serde(default)]
+pub struct AssetManifest {
+    pub celebrities: Vec<Asset>,
+    pub fonts: Vec<Asset>,
+}
+
+impl AssetManifest {
+    /// pathのJSONファイルからマニフェストを読み込む
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AssetError> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    /// 素材として正しいか検証する
+    /// IDは全ての素材で一意で、著名人には画像、フォントにはフォントファミリーが必要
+    pub fn validate(&self) -> Result<(), AssetError> {
+        let mut ids = HashSet::new();
+        for asset in self.celebrities.iter().chain(&self.fonts) {
+            if !ids.insert(&asset.id) {
+                return Err(AssetError::DuplicateId {
+                    id: asset.id.clone(),
+                });
+            }
+        }
+
+        if let Some(asset) = self.celebrities.iter().find(|a| a.image.is_none()) {
+            return Err(AssetError::MissingImage {
+                id: asset.id.clone(),
+            });
+        }
+        if let Some(asset) = self.fonts.iter().find(|a| a.family.is_none()) {
+            return Err(AssetError::MissingFamily {
+                id: asset.id.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// modeで使う素材 (素材を使わないモードでは空)
+    pub fn assets(&self, mode: &GameMode) -> &[Asset] {
+        match mode {
+            GameMode::CELEBRITY => &self.celebrities,
+            GameMode::FONT => &self.fonts,
+            _ => &[],
+        }
+    }
+
+    /// rangeの全ての数字にmodeの素材を割り当てられるか検証する
+    pub fn validate_for(&self, mode: &GameMode, range: NumberRange) -> Result<(), Error> {
+        let count = range.count();
+        let available = self.assets(mode).len();
+        if count > available {
+            return Err(Error::NotEnoughAssets { count, available });
+        }
+
+        Ok(())
+    }
+
+    /// rangeの数字にmodeの素材を割り当てる
+    /// 割り当てはseedから決まるため、同じゲームでは同じ素材になる
+    pub fn assign(
+        &self,
+        mode: &GameMode,
+        range: NumberRange,
+        seed: u64,
+    ) -> Result<AssetAssignment, Error> {
+        self.validate_for(mode, range)?;
+
+        let mut assets = self.assets(mode).to_vec();
+        assets.shuffle(&mut StdRng::seed_from_u64(seed));
+        assets.truncate(range.count());
+
+        Ok(AssetAssignment {
+            min: range.min,
+            assets,
+        })
+    }
+}
+
+impl std::str::FromStr for AssetManifest {
+    type Err = AssetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let manifest: AssetManifest = serde_json::from_str(s)?;
+        manifest.validate()?;
+
+        Ok(manifest)
+    }
+}
+
+/// ゲームでの数字と素材の対応
+#[derive(Clone, PartialEq, Debug)]
+pub struct AssetAssignment {
+    /// 最初の素材に対応する数字
+    min: usize,
+    assets: Vec<Asset>,
+}
+
+impl AssetAssignment {
+    /// numberに割り当てられた素材
+    pub fn asset(&self, number: usize) -> Option<&Asset> {
+        self.assets.get(number.checked_sub(self.min)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 著名人とフォントをcount個ずつ持つマニフェスト
+    fn manifest(count: usize) -> AssetManifest {
+        AssetManifest {
+            celebrities: (0..count)
+                .map(|i| Asset {
+                    id: format!("celebrity-{i}"),
+                    name: format!("Celebrity {i}"),
+                    image: Some(format!("celebrity/{i}.png")),
+                    family: None,
+                })
+                .collect(),
+            fonts: (0..count)
+                .map(|i| Asset {
+                    id: format!("font-{i}"),
+                    name: format!("Font {i}"),
+                    image: None,
+                    family: Some(format!("Family {i}")),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn it_reads_manifest() {
+        let manifest: AssetManifest = r#"{
+            "celebrities": [{ "id": "einstein", "name": "Albert Einstein", "image": "celebrity/einstein.png" }],
+            "fonts": [{ "id": "noto-serif", "name": "Noto Serif", "family": "Noto Serif JP" }]
+        }"#
+        .parse()
+        .unwrap();
+
+        assert_eq!(manifest.celebrities[0].name, "Albert Einstein");
+        assert_eq!(
+            manifest.assets(&GameMode::FONT)[0].family.as_deref(),
+            Some("Noto Serif JP")
+        );
+        assert!(manifest.assets(&GameMode::NORMAL).is_empty());
+    }
+
+    #[test]
+    fn it_rejects_invalid_manifest() {
+        let invalid = [
+            r#"{"celebrities":[{"id":"a","name":"A","image":"a.png"}],"fonts":[{"id":"a","name":"A","family":"A"}]}"#,
+            r#"{"celebrities":[{"id":"a","name":"A"}]}"#,
+            r#"{"fonts":[{"id":"a","name":"A"}]}"#,
+            r#"{"fonts":"a"}"#,
+        ];
+
+        for json in invalid {
+            assert!(json.parse::<AssetManifest>().is_err(), "{json}");
+        }
+    }
+
+    #[test]
+    fn it_requires_assets_for_every_number() {
+        let manifest = manifest(74);
+        let range = NumberRange { min: 1, max: 75 };
+
+        assert_eq!(
+            manifest.validate_for(&GameMode::CELEBRITY, range),
+            Err(Error::NotEnoughAssets {
+                count: 75,
+                available: 74
+            })
+        );
+        assert!(manifest
+            .validate_for(&GameMode::FONT, NumberRange { min: 1, max: 74 })
+            .is_ok());
+    }
+
+    #[test]
+    fn it_assigns_distinct_assets_deterministically() {
+        let manifest = manifest(100);
+        let range = NumberRange { min: 11, max: 85 };
+
+        let a = manifest.assign(&GameMode::CELEBRITY, range, 0).unwrap();
+        let b = manifest.assign(&GameMode::CELEBRITY, range, 0).unwrap();
+        let c = manifest.assign(&GameMode::CELEBRITY, range, 1).unwrap();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+
+        let ids: HashSet<&String> = (11..=85).map(|n| &a.asset(n).unwrap().id).collect();
+        assert_eq!(ids.len(), 75);
+        assert_eq!(a.asset(10), None);
+        assert_eq!(a.asset(86), None);
+    }
+}
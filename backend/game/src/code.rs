@@ -0,0 +1,62 @@
+use rand::Rng;
+
+/// 参加コードの長さ
+pub const CODE_LENGTH: usize = 6;
+
+/// 参加コードに使う文字 (見間違えやすい0とO、1とIとLは使わない)
+const CODE_CHARS: &[u8] = b"ABCDEFGHJKMNPQRSTUVWXYZ23456789";
+
+/// 参加コードを作る
+pub fn generate(rng: &mut impl Rng) -> String {
+    (0..CODE_LENGTH)
+        .map(|_| CODE_CHARS[rng.gen_range(0..CODE_CHARS.len())] as char)
+        .collect()
+}
+
+/// 入力された参加コードを揃える (参加コードとして正しくない場合はNone)
+/// 小文字、前後の空白、区切りのハイフンは許す
+///
+/// # 例
+///
+/// ```
+/// # use game::code::normalize;
+/// assert_eq!(normalize(" abc-234 "), Some("ABC234".to_string()));
+/// assert_eq!(normalize("ABC10O"), None);
+/// ```
+pub fn normalize(input: &str) -> Option<String> {
+    let code: String = input
+        .trim()
+        .chars()
+        .filter(|&c| c != '-')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    let valid = code.len() == CODE_LENGTH && code.bytes().all(|c| CODE_CHARS.contains(&c));
+    valid.then_some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn it_generates_normalized_codes() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..1000 {
+            let code = generate(&mut rng);
+            assert_eq!(code.len(), CODE_LENGTH);
+            assert_eq!(normalize(&code), Some(code.clone()));
+            assert!(!code.contains(['0', 'O', '1', 'I', 'L']), "{code}");
+        }
+    }
+
+    #[test]
+    fn it_rejects_invalid_codes() {
+        for input in ["", "ABC23", "ABC2345", "ABC 23", "ABCD0E", "ÀBCDEF"] {
+            assert_eq!(normalize(input), None, "{input}");
+        }
+    }
+}
@@ -0,0 +1,306 @@
+use crate::errors::Error;
+use board::generate::NumberRange;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// パレットに入れられる色の最大数
+pub const MAX_COLORS: usize = 150;
+/// パレットの色同士の最小の色差 (ΔE*ab)
+pub const MIN_DELTA_E: f64 = 10.0;
+/// 1色を選ぶときに比べる候補の数
+const CANDIDATES: usize = 64;
+/// 候補を引き直す回数の上限
+const ROUNDS: usize = 16;
+/// シードを変えて最初から選び直す回数の上限
+const ATTEMPTS: u64 = 8;
+
+/// sRGBの色
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    /// CIE L*a*b* (D65)
+    fn lab(self) -> [f64; 3] {
+        let linear = |c: u8| {
+            let c = f64::from(c) / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        let (r, g, b) = (linear(self.r), linear(self.g), linear(self.b));
+
+        let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+        let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+
+        let f = |t: f64| {
+            if t > 0.008856 {
+                t.cbrt()
+            } else {
+                7.787 * t + 16.0 / 116.0
+            }
+        };
+        let (fx, fy, fz) = (f(x), f(y), f(z));
+
+        [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+    }
+
+    /// otherとの色差 (CIE76のΔE*ab)
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use game::color::Color;
+    /// let black = Color { r: 0, g: 0, b: 0 };
+    /// let white = Color { r: 255, g: 255, b: 255 };
+    /// assert!((black.delta_e(white) - 100.0).abs() < 0.1);
+    /// assert_eq!(white.delta_e(white), 0.0);
+    /// ```
+    pub fn delta_e(self, other: Color) -> f64 {
+        distance(self.lab(), other.lab())
+    }
+
+    /// 色相 (0以上360未満)、彩度と輝度 (0以上100以下)
+    fn hsl(self) -> (u32, u32, u32) {
+        let (r, g, b) = (
+            f64::from(self.r) / 255.0,
+            f64::from(self.g) / 255.0,
+            f64::from(self.b) / 255.0,
+        );
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let lightness = (max + min) / 2.0;
+
+        if max == min {
+            return (0, 0, (lightness * 100.0).round() as u32);
+        }
+
+        let d = max - min;
+        let saturation = d / (1.0 - (2.0 * lightness - 1.0).abs());
+        let hue = if max == r {
+            ((g - b) / d).rem_euclid(6.0)
+        } else if max == g {
+            (b - r) / d + 2.0
+        } else {
+            (r - g) / d + 4.0
+        };
+
+        (
+            (hue * 60.0).round() as u32 % 360,
+            (saturation * 100.0).round() as u32,
+            (lightness * 100.0).round() as u32,
+        )
+    }
+}
+
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a.iter()
+        .zip(&b)
+        .map(|(a, b)| (a - b).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// 色の表記
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum ColorFormat {
+    /// `#RRGGBB`
+    #[default]
+    HEX,
+    /// `rgb(r, g, b)`
+    RGB,
+    /// `hsl(h, s%, l%)`
+    HSL,
+}
+
+impl ColorFormat {
+    /// # 例
+    ///
+    /// ```
+    /// # use game::color::{Color, ColorFormat};
+    /// let color = Color { r: 255, g: 128, b: 0 };
+    /// assert_eq!(ColorFormat::HEX.format(color), "#FF8000");
+    /// assert_eq!(ColorFormat::RGB.format(color), "rgb(255, 128, 0)");
+    /// assert_eq!(ColorFormat::HSL.format(color), "hsl(30, 100%, 50%)");
+    /// ```
+    pub fn format(self, color: Color) -> String {
+        let Color { r, g, b } = color;
+
+        match self {
+            ColorFormat::HEX => format!("#{r:02X}{g:02X}{b:02X}"),
+            ColorFormat::RGB => format!("rgb({r}, {g}, {b})"),
+            ColorFormat::HSL => {
+                let (h, s, l) = color.hsl();
+                format!("hsl({h}, {s}%, {l}%)")
+            }
+        }
+    }
+}
+
+/// 数字に対応する色の一覧
+///
+/// 色はシードから決まるため、同じシードであれば同じパレットになる
+#[derive(Clone, PartialEq, Debug)]
+pub struct Palette {
+    /// rangeの最小値から順に対応する色
+    colors: Vec<Color>,
+    range: NumberRange,
+}
+
+impl Palette {
+    /// rangeの数字それぞれに見分けられる色を割り当てる
+    ///
+    /// 既に選んだ色から最も離れた候補を1色ずつ選び、`MIN_DELTA_E`未満の候補しかない場合は候補を引き直す。
+    /// 引き直しても見つからない場合はシードから導いた別のシードで最初から選び直すため、
+    /// 同じシードであれば結果は変わらない。
+    /// それでも見つからない場合は`Error::PaletteExhausted`を返すため、全ての色の組が`MIN_DELTA_E`以上離れる。
+    pub fn new(seed: u64, range: NumberRange) -> Result<Self, Error> {
+        let count = range.count();
+        if count > MAX_COLORS {
+            return Err(Error::TooManyColors {
+                count,
+                max: MAX_COLORS,
+            });
+        }
+
+        (0..ATTEMPTS)
+            .find_map(|attempt| {
+                // 1回目は与えられたシードをそのまま使う
+                let seed = seed.wrapping_add(attempt.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+                pick_colors(seed, count)
+            })
+            .map(|colors| Self { colors, range })
+            .ok_or(Error::PaletteExhausted { count })
+    }
+
+    /// 数字に対応する色
+    pub fn color(&self, number: usize) -> Option<Color> {
+        self.colors
+            .get(number.checked_sub(self.range.min)?)
+            .copied()
+    }
+}
+
+/// 見分けられるcount色を選ぶ (選べなかった場合はNone)
+fn pick_colors(seed: u64, count: usize) -> Option<Vec<Color>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut colors: Vec<Color> = vec![];
+    let mut labs: Vec<[f64; 3]> = vec![];
+
+    while colors.len() < count {
+        let nearest = |lab: &[f64; 3]| {
+            labs.iter()
+                .map(|other| distance(*lab, *other))
+                .fold(f64::INFINITY, f64::min)
+        };
+        let (color, lab) = (0..ROUNDS)
+            .filter_map(|_| {
+                (0..CANDIDATES)
+                    .map(|_| {
+                        let color = Color {
+                            r: rng.gen(),
+                            g: rng.gen(),
+                            b: rng.gen(),
+                        };
+                        (color, color.lab())
+                    })
+                    .max_by(|(_, a), (_, b)| nearest(a).total_cmp(&nearest(b)))
+            })
+            .find(|(_, lab)| nearest(lab) >= MIN_DELTA_E)?;
+
+        colors.push(color);
+        labs.push(lab);
+    }
+
+    Some(colors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn min_delta_e(palette: &Palette) -> f64 {
+        let mut min = f64::INFINITY;
+        for (i, a) in palette.colors.iter().enumerate() {
+            for b in &palette.colors[i + 1..] {
+                min = min.min(a.delta_e(*b));
+            }
+        }
+        min
+    }
+
+    #[test]
+    fn it_keeps_colors_distinct() {
+        for seed in (0..10).chain([u64::MAX, 0x9E37_79B9_7F4A_7C15, 1 << 63]) {
+            let palette = Palette::new(
+                seed,
+                NumberRange {
+                    min: 1,
+                    max: MAX_COLORS,
+                },
+            )
+            .unwrap();
+            assert_eq!(palette.colors.len(), MAX_COLORS);
+            assert!(min_delta_e(&palette) >= MIN_DELTA_E, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn it_picks_counts_below_max_colors() {
+        for seed in 0..4 {
+            for count in [1, 2, MAX_COLORS / 2, MAX_COLORS - 1] {
+                let palette = Palette::new(seed, NumberRange { min: 1, max: count }).unwrap();
+                assert_eq!(palette.colors.len(), count, "seed {seed}");
+            }
+        }
+    }
+
+    #[test]
+    fn it_is_reproducible_with_same_seed() {
+        let range = NumberRange::for_size(5);
+
+        assert_eq!(Palette::new(7, range), Palette::new(7, range));
+        assert_ne!(Palette::new(7, range), Palette::new(8, range));
+    }
+
+    #[test]
+    fn it_maps_numbers_from_range_min() {
+        let palette = Palette::new(0, NumberRange { min: 10, max: 19 }).unwrap();
+
+        assert_eq!(palette.color(10), Some(palette.colors[0]));
+        assert_eq!(palette.color(9), None);
+        assert_eq!(palette.color(20), None);
+    }
+
+    #[test]
+    fn it_counts_colors_without_overflow() {
+        let palette = Palette::new(
+            0,
+            NumberRange {
+                min: usize::MAX - 1,
+                max: usize::MAX,
+            },
+        )
+        .unwrap();
+        assert_eq!(palette.colors.len(), 2);
+        assert_eq!(palette.color(usize::MAX), Some(palette.colors[1]));
+    }
+
+    #[test]
+    fn it_rejects_too_many_colors() {
+        assert_eq!(
+            Palette::new(0, NumberRange { min: 1, max: 225 }),
+            Err(Error::TooManyColors {
+                count: 225,
+                max: MAX_COLORS
+            })
+        );
+    }
+}
@@ -1,7 +1,19 @@
+use crate::color::ColorFormat;
+use crate::errors::Error;
+use crate::formula::Difficulty;
+use board::dice::DiceSet;
+use board::generate::NumberRange;
+use board::pattern::Pattern;
 use serde::{Deserialize, Serialize};
 
+/// 標準の数字盤のサイズ
+pub const DEFAULT_BOARD_SIZE: usize = 5;
+/// 標準の異国語数字の書き表し方
+pub const DEFAULT_NUMERAL: &str = "KANJI";
+
 /// ゲームモード
-#[derive(Serialize, Deserialize, Clone, Debug)]
+/// モードごとの遊び方は`rules::registry`から引く
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum GameMode {
     NORMAL,
     /// パチスロ
@@ -32,11 +44,133 @@ pub enum GameMode {
     FOREIGN,
 }
 
-#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
 pub struct GameSettings {
     /// 何回でもビンゴできるかどうか
+    /// 無効な場合は1人1回目のビンゴのみが認められる
     pub multiple_bingo: bool,
+    /// 抽選された数字を全員の数字盤で自動的に開けるかどうか
+    /// 無効な場合は各プレイヤーが抽選済みの数字を自分で開ける
     pub auto_open: bool,
     /// 最大プレイヤー数
     pub max_player: Option<usize>,
+    /// 数字盤のサイズ (奇数)
+    pub board_size: usize,
+    /// 数字盤に書かれうる数字の範囲
+    /// 指定しない場合は1列あたり15個 (5x5なら1から75)
+    pub number_range: Option<NumberRange>,
+    /// ビンゴと認められるパターン (いずれかが揃えばビンゴ)
+    pub patterns: Vec<Pattern>,
+    /// サイコロモードで振るサイコロの組
+    /// 指定しない場合は20面のサイコロ3つの合計
+    pub dice: Option<DiceSet>,
+    /// 数式モードで抽選された数字を表す数式の難しさ
+    pub difficulty: Difficulty,
+    /// 色モードで抽選された色の表記
+    pub color_format: ColorFormat,
+    /// 異国語数字モードで抽選された数字の書き表し方 (`GameManager`に登録された名前)
+    pub numeral: String,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self {
+            multiple_bingo: false,
+            auto_open: false,
+            max_player: None,
+            board_size: DEFAULT_BOARD_SIZE,
+            number_range: None,
+            patterns: vec![Pattern::LINE],
+            dice: None,
+            difficulty: Difficulty::default(),
+            color_format: ColorFormat::default(),
+            numeral: DEFAULT_NUMERAL.to_string(),
+        }
+    }
+}
+
+impl GameSettings {
+    /// 数字盤に書かれうる数字の範囲
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use board::generate::NumberRange;
+    /// # use game::config::GameSettings;
+    /// let settings = GameSettings { board_size: 7, ..Default::default() };
+    /// assert_eq!(settings.range(), NumberRange { min: 1, max: 105 });
+    /// ```
+    pub fn range(&self) -> NumberRange {
+        self.number_range
+            .unwrap_or_else(|| NumberRange::for_size(self.board_size))
+    }
+
+    /// サイコロモードで振るサイコロの組
+    pub fn dice_set(&self) -> DiceSet {
+        self.dice.clone().unwrap_or_default()
+    }
+
+    /// ゲームモードによらない設定を検証する
+    /// 数字の範囲などモードごとの検証は`ModeRules::validate_settings`で行う
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.patterns.is_empty() {
+            return Err(board::errors::Error::NoPatterns.into());
+        }
+        for pattern in &self.patterns {
+            pattern.validate(self.board_size)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_fills_missing_settings_with_default() {
+        let settings: GameSettings = serde_json::from_str(r#"{"auto_open":true}"#).unwrap();
+
+        assert!(settings.auto_open);
+        assert_eq!(settings.board_size, DEFAULT_BOARD_SIZE);
+        assert_eq!(settings.range(), NumberRange { min: 1, max: 75 });
+    }
+
+    #[test]
+    fn it_reads_patterns() {
+        let settings: GameSettings = serde_json::from_str(
+            r#"{"patterns":["CORNERS",{"CUSTOM":{"cells":[[true,false,false],[false,true,false],[false,false,true]]}}],"board_size":3}"#,
+        )
+        .unwrap();
+
+        assert_eq!(settings.patterns[0], Pattern::CORNERS);
+        assert_eq!(settings.validate(), Ok(()));
+    }
+
+    #[test]
+    fn it_rejects_invalid_patterns() {
+        let settings = GameSettings {
+            patterns: vec![],
+            ..Default::default()
+        };
+        assert_eq!(
+            settings.validate(),
+            Err(Error::Board(board::errors::Error::NoPatterns))
+        );
+
+        let settings = GameSettings {
+            patterns: vec![Pattern::CUSTOM {
+                cells: vec![vec![true; 3]; 3],
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            settings.validate(),
+            Err(Error::Board(board::errors::Error::InvalidPattern {
+                size: 5
+            }))
+        );
+    }
 }
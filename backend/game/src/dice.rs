@@ -0,0 +1,51 @@
+use board::dice::{DiceSet, Roll, RollTable};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// サイコロを振る係
+///
+/// 出目はシードから決まるため、同じシードであれば同じ順番で出る
+#[derive(Clone, Debug)]
+pub struct DiceRoller {
+    dice: DiceSet,
+    table: RollTable,
+    rng: StdRng,
+}
+
+impl DiceRoller {
+    pub fn new(dice: DiceSet, seed: u64) -> Self {
+        Self {
+            table: dice.table(),
+            dice,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// 振るサイコロの組
+    pub fn dice(&self) -> &DiceSet {
+        &self.dice
+    }
+
+    /// candidatesのいずれかが出るまでサイコロを振る
+    /// 実際には振り直さず、出る確率で重み付けして数字を選んでからその数字になる出目を選ぶ
+    /// どの数字も出ない場合はNone
+    pub fn roll_among(&mut self, candidates: &[usize]) -> Option<Roll> {
+        self.table.roll_among(candidates, &mut self.rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_is_reproducible_with_same_seed() {
+        let mut a = DiceRoller::new(DiceSet::default(), 7);
+        let mut b = DiceRoller::new(DiceSet::default(), 7);
+
+        let candidates: Vec<usize> = (3..=60).collect();
+        for _ in 0..100 {
+            assert_eq!(a.roll_among(&candidates), b.roll_among(&candidates));
+        }
+    }
+}
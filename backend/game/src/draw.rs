@@ -0,0 +1,223 @@
+use crate::asset::Asset;
+use crate::errors::Error;
+use crate::ranking::Bingo;
+use crate::slot::Spin;
+use board::board::OpenResult;
+use board::dice::Roll;
+use board::tile::Tile;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use serenity::all::UserId;
+use std::collections::HashMap;
+
+/// 数字の抽選機
+///
+/// 抽選される数字はシードから決まるため、同じシードであれば同じ順番で抽選される
+#[derive(Serialize, Clone, Debug)]
+pub struct Drawer {
+    /// まだ抽選されていない数字 (末尾から抽選される)
+    #[serde(skip)]
+    pool: Vec<usize>,
+    /// 抽選済みの数字 (抽選順)
+    history: Vec<usize>,
+}
+
+impl Drawer {
+    /// numbersをシャッフルした抽選機を作る
+    /// numbersに重複があった場合は1つにまとめられる
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use game::draw::Drawer;
+    /// let mut drawer = Drawer::new(0, 1..=3);
+    /// let mut drawn = vec![];
+    /// while let Ok(number) = drawer.draw_next() {
+    ///     drawn.push(number);
+    /// }
+    /// drawn.sort();
+    /// assert_eq!(drawn, vec![1, 2, 3]);
+    /// ```
+    pub fn new(seed: u64, numbers: impl IntoIterator<Item = usize>) -> Self {
+        let mut pool: Vec<usize> = numbers.into_iter().collect();
+        pool.sort_unstable();
+        pool.dedup();
+        pool.shuffle(&mut StdRng::seed_from_u64(seed));
+
+        Self {
+            pool,
+            history: vec![],
+        }
+    }
+
+    /// 次の数字を抽選する
+    pub fn draw_next(&mut self) -> Result<usize, Error> {
+        let number = self.pool.pop().ok_or(Error::NoNumbersLeft)?;
+        self.history.push(number);
+
+        Ok(number)
+    }
+
+    /// まだ抽選されていない数字のうちindex番目 (個数で割った余り) を抽選する
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use game::draw::Drawer;
+    /// let mut drawer = Drawer::new(0, 1..=3);
+    /// let number = drawer.draw_index(1000).unwrap();
+    /// assert_eq!(drawer.history(), &[number]);
+    /// assert_eq!(drawer.remaining(), 2);
+    /// ```
+    pub fn draw_index(&mut self, index: usize) -> Result<usize, Error> {
+        if self.pool.is_empty() {
+            return Err(Error::NoNumbersLeft);
+        }
+
+        let number = self.pool.remove(index % self.pool.len());
+        self.history.push(number);
+
+        Ok(number)
+    }
+
+    /// 指定した数字を抽選済みにする
+    pub fn draw(&mut self, number: usize) -> Result<usize, Error> {
+        match self.pool.iter().position(|&x| x == number) {
+            Some(index) => {
+                self.pool.remove(index);
+                self.history.push(number);
+
+                Ok(number)
+            }
+            None if self.is_drawn(number) => Err(Error::AlreadyDrawn { number }),
+            None => Err(Error::InvalidNumber { number }),
+        }
+    }
+
+    /// 抽選済みの数字 (抽選順)
+    pub fn history(&self) -> &[usize] {
+        &self.history
+    }
+
+    pub fn is_drawn(&self, number: usize) -> bool {
+        self.history.contains(&number)
+    }
+
+    /// まだ抽選されていない数字 (`draw_next`で抽選される順の逆順)
+    pub fn undrawn(&self) -> &[usize] {
+        &self.pool
+    }
+
+    /// まだ抽選されていない数字の数
+    pub fn remaining(&self) -> usize {
+        self.pool.len()
+    }
+}
+
+/// ゲームモード毎の抽選の詳細
+///
+/// バリアントの追加は互換な変更とみなし、`PROTOCOL_VERSION`は上げない
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum DrawDetail {
+    /// スロットを回した結果
+    SLOT(Spin),
+    /// サイコロを振った結果
+    DICE(Roll),
+    /// 山から引いた牌
+    /// wallは山に残っている牌の数
+    MAHJONG { tile: Tile, wall: usize },
+    /// 抽選された数字を値とする数式
+    FORMULA { expression: String },
+    /// 抽選された数字に割り当てられた素材
+    ASSET(Asset),
+}
+
+/// 抽選の結果
+#[derive(Clone, PartialEq, Debug)]
+pub struct DrawResult {
+    /// 抽選された数字
+    pub number: usize,
+    /// 抽選の詳細 (数字を直接指定した場合やボーナスで抽選された場合はない)
+    /// 数式モードと素材を使うモードでは数字を直接指定した場合も付く
+    pub detail: Option<DrawDetail>,
+    /// ゲームモードでの数字の表記 (数字のままの場合はない)
+    pub label: Option<String>,
+    /// 各参加者の数字盤を開けた結果
+    /// `GameSettings::auto_open`が無効な場合は空になる
+    pub opened: HashMap<UserId, OpenResult>,
+    /// この抽選で新しく認められたビンゴ
+    pub bingos: Vec<Bingo>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_draws_every_number_once() {
+        let mut drawer = Drawer::new(0, 1..=75);
+
+        for _ in 0..75 {
+            drawer.draw_next().unwrap();
+        }
+        assert_eq!(drawer.draw_next(), Err(Error::NoNumbersLeft));
+        assert_eq!(drawer.remaining(), 0);
+
+        let mut history = drawer.history().to_vec();
+        history.sort();
+        assert_eq!(history, (1..=75).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn it_is_reproducible_with_same_seed() {
+        let mut a = Drawer::new(42, 1..=75);
+        let mut b = Drawer::new(42, 1..=75);
+        let mut c = Drawer::new(43, 1..=75);
+
+        for _ in 0..75 {
+            a.draw_next().unwrap();
+            b.draw_next().unwrap();
+            c.draw_next().unwrap();
+        }
+        assert_eq!(a.history(), b.history());
+        assert_ne!(a.history(), c.history());
+    }
+
+    #[test]
+    fn it_draws_by_index() {
+        let mut drawer = Drawer::new(0, 1..=75);
+
+        for i in 0..75 {
+            drawer.draw_index(i * 31).unwrap();
+        }
+        assert_eq!(drawer.draw_index(0), Err(Error::NoNumbersLeft));
+
+        let mut history = drawer.history().to_vec();
+        history.sort();
+        assert_eq!(history, (1..=75).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn it_merges_duplicated_numbers() {
+        let drawer = Drawer::new(0, [1, 1, 2, 2, 3]);
+        assert_eq!(drawer.remaining(), 3);
+    }
+
+    #[test]
+    fn it_rejects_duplicated_draw() {
+        let mut drawer = Drawer::new(0, 1..=75);
+
+        assert_eq!(drawer.draw(15), Ok(15));
+        assert_eq!(drawer.draw(15), Err(Error::AlreadyDrawn { number: 15 }));
+        assert_eq!(drawer.draw(76), Err(Error::InvalidNumber { number: 76 }));
+        assert_eq!(drawer.history(), &[15]);
+        assert_eq!(drawer.remaining(), 74);
+
+        while drawer.draw_next().is_ok() {}
+        let mut history = drawer.history().to_vec();
+        history.sort();
+        assert_eq!(history, (1..=75).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+/// 数字の表記
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Encoding {
+    /// 10進数
+    DECIMAL,
+    /// 2進数 (`0b`から始まる)
+    BINARY,
+    /// 16進数 (`0x`から始まる)
+    HEXADECIMAL,
+}
+
+impl Encoding {
+    /// numberをこの表記にする
+    /// 桁数はmaxを表すのに必要な桁数に0で揃える
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use game::encoding::Encoding;
+    /// assert_eq!(Encoding::DECIMAL.encode(5, 75), "5");
+    /// assert_eq!(Encoding::BINARY.encode(5, 75), "0b0000101");
+    /// assert_eq!(Encoding::HEXADECIMAL.encode(75, 75), "0x4B");
+    /// ```
+    pub fn encode(self, number: usize, max: usize) -> String {
+        match self {
+            Encoding::DECIMAL => number.to_string(),
+            Encoding::BINARY => {
+                let width = format!("{max:b}").len();
+                format!("0b{number:0width$b}")
+            }
+            Encoding::HEXADECIMAL => {
+                let width = format!("{max:X}").len();
+                format!("0x{number:0width$X}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_pads_to_width_of_max() {
+        assert_eq!(Encoding::BINARY.encode(1, 1), "0b1");
+        assert_eq!(Encoding::BINARY.encode(1, 255), "0b00000001");
+        assert_eq!(Encoding::HEXADECIMAL.encode(255, 256), "0x0FF");
+        assert_eq!(Encoding::DECIMAL.encode(7, 225), "7");
+    }
+}
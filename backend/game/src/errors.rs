@@ -1,3 +1,5 @@
+use crate::config::GameMode;
+use crate::status::GameStatus;
 use serenity::all::UserId;
 
 #[derive(thiserror::Error, PartialEq, Debug)]
@@ -5,12 +7,63 @@ pub enum Error {
     #[error("Max players reached")]
     MaxPlayers,
 
-    #[error("Cannot generate board")]
-    BoardGenerationError,
+    #[error(transparent)]
+    Board(#[from] board::errors::Error),
 
     #[error("Game not found with ID {game_id}")]
     NotFound { game_id: u32 },
 
+    #[error("No game found with join code {code}")]
+    UnknownCode { code: String },
+
+    #[error("User {user} is not participating in the game")]
+    NotParticipant { user: UserId },
+
+    #[error("User {user} is not the host of the game")]
+    NotHost { user: UserId },
+
+    #[error("Board has no bingo")]
+    NoBingo,
+
+    #[error("User {user} has already claimed the bingo")]
+    AlreadyClaimed { user: UserId },
+
+    #[error("No numbers left to draw")]
+    NoNumbersLeft,
+
+    #[error("Number {number} has already been drawn")]
+    AlreadyDrawn { number: usize },
+
+    #[error("Number {number} cannot be drawn in this game")]
+    InvalidNumber { number: usize },
+
+    #[error("Number {number} has not been drawn yet")]
+    NotDrawn { number: usize },
+
+    #[error("Cannot change game status from {from:?} to {to:?}")]
+    InvalidTransition { from: GameStatus, to: GameStatus },
+
+    #[error("Operation not allowed while the game is {status:?}")]
+    InvalidStatus { status: GameStatus },
+
+    #[error("Cannot pick {count} distinct colors (at most {max})")]
+    TooManyColors { count: usize, max: usize },
+
+    #[error("Could not pick {count} distinct colors")]
+    PaletteExhausted { count: usize },
+
+    #[error("Unknown numeral system {name}")]
+    UnknownNumeral { name: String },
+
+    #[error("Numeral system {name} can only write numbers up to {max}")]
+    NumeralOutOfRange { name: String, max: usize },
+
+    #[error("Only {available} assets are available, not enough for {count} numbers")]
+    NotEnoughAssets { count: usize, available: usize },
+
+    #[error("Game mode {mode:?} is not supported")]
+    UnsupportedMode { mode: GameMode },
+
     #[error("User {host} already has an ongoing game with ID {game_id}")]
     OngoingGame { host: UserId, game_id: u32 },
 }
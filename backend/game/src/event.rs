@@ -0,0 +1,147 @@
+use crate::draw::DrawDetail;
+use crate::ranking::Rank;
+use crate::status::GameStatus;
+use board::analysis::ReachKind;
+use board::pattern::Pattern;
+use serde::{Deserialize, Serialize};
+use serenity::all::UserId;
+
+/// WebSocketでやり取りするメッセージのバージョン
+/// スキーマに互換性のない変更を加えた場合は上げること
+///
+/// # 変更履歴
+///
+/// - 2: `GameFinished`に順位 (`ranking`) を追加、`StatusChanged`と`ChangeStatus`を追加
+/// - 3: `Bingo`に揃ったパターン (`patterns`) を追加
+/// - 4: `Reach`にリーチの種類 (`kind`) を追加
+/// - 5: `NumberDrawn`に抽選の詳細 (`detail`) を追加
+/// - 6: `NumberDrawn`にゲームモードでの表記 (`label`) を追加
+///
+/// # 互換な変更
+///
+/// 次の変更ではバージョンを上げない。クライアントは知らないバリアントを無視すること。
+///
+/// - `DrawDetail`へのバリアントの追加: `MAHJONG`、`FORMULA`、`ASSET`
+pub const PROTOCOL_VERSION: u32 = 6;
+
+/// バージョン付きのメッセージ
+///
+/// # 例
+///
+/// ```
+/// # use game::event::{Message, ServerEvent};
+/// let message = Message::new(ServerEvent::GameFinished { ranking: vec![] });
+/// assert_eq!(
+///     serde_json::to_string(&message).unwrap(),
+///     r#"{"version":6,"type":"GameFinished","ranking":[]}"#
+/// );
+/// ```
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Message<T> {
+    pub version: u32,
+    #[serde(flatten)]
+    pub payload: T,
+}
+
+impl<T> Message<T> {
+    pub fn new(payload: T) -> Self {
+        Self {
+            version: PROTOCOL_VERSION,
+            payload,
+        }
+    }
+}
+
+/// サーバーからクライアントへ送るイベント
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(tag = "type")]
+pub enum ServerEvent {
+    /// プレイヤーが参加した
+    PlayerJoined { user: UserId },
+    /// 数字が抽選された
+    /// detailはパチスロモードのリールの結果など、抽選方法ごとの詳細
+    /// labelは2進数モードなどでの数字の表記
+    NumberDrawn {
+        number: usize,
+        detail: Option<DrawDetail>,
+        label: Option<String>,
+    },
+    /// リーチになった
+    /// kindはリーチになっている全ライン数から決まる (ダブルリーチなど)
+    Reach {
+        user: UserId,
+        lines: Vec<Vec<usize>>,
+        kind: ReachKind,
+    },
+    /// ビンゴになった
+    Bingo {
+        user: UserId,
+        lines: Vec<Vec<usize>>,
+        patterns: Vec<Pattern>,
+    },
+    /// ゲームの状態が変わった
+    StatusChanged { status: GameStatus },
+    /// ゲームが終了した
+    GameFinished { ranking: Vec<Rank> },
+    /// コマンドの処理に失敗した (送信したクライアントにのみ送られる)
+    Error { message: String },
+}
+
+/// クライアントからサーバーへ送るコマンド
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(tag = "type")]
+pub enum ClientCommand {
+    /// 数字を開ける
+    Mark { number: usize },
+    /// ビンゴを申告する
+    ClaimBingo,
+    /// 数字を抽選する (ホストのみ)
+    /// numberを指定した場合はその数字を抽選済みにする
+    Draw { number: Option<usize> },
+    /// ゲームの状態を変える (ホストのみ)
+    ChangeStatus { status: GameStatus },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_can_serialize_server_event() {
+        let message = Message::new(ServerEvent::Reach {
+            user: UserId::new(1),
+            lines: vec![vec![1, 2, 0]],
+            kind: ReachKind::DOUBLE,
+        });
+
+        assert_eq!(
+            serde_json::to_string(&message).unwrap(),
+            r#"{"version":6,"type":"Reach","user":"1","lines":[[1,2,0]],"kind":"DOUBLE"}"#
+        );
+    }
+
+    #[test]
+    fn it_can_deserialize_client_command() {
+        assert_eq!(
+            serde_json::from_str::<Message<ClientCommand>>(
+                r#"{"version":6,"type":"Mark","number":15}"#
+            )
+            .unwrap(),
+            Message::new(ClientCommand::Mark { number: 15 })
+        );
+        assert_eq!(
+            serde_json::from_str::<Message<ClientCommand>>(r#"{"version":6,"type":"ClaimBingo"}"#)
+                .unwrap(),
+            Message::new(ClientCommand::ClaimBingo)
+        );
+        assert_eq!(
+            serde_json::from_str::<Message<ClientCommand>>(
+                r#"{"version":6,"type":"ChangeStatus","status":"RUNNING"}"#
+            )
+            .unwrap(),
+            Message::new(ClientCommand::ChangeStatus {
+                status: GameStatus::RUNNING
+            })
+        );
+    }
+}
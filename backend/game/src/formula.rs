@@ -0,0 +1,365 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 数式モードの難しさ
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Difficulty {
+    /// 足し算と引き算を1回
+    EASY,
+    /// 掛け算を含めて2回
+    #[default]
+    NORMAL,
+    /// 割り算と括弧を含めて3回
+    HARD,
+}
+
+impl Difficulty {
+    /// 使える演算子
+    fn operators(self) -> &'static [Operator] {
+        match self {
+            Difficulty::EASY => &[Operator::ADD, Operator::SUB],
+            Difficulty::NORMAL => &[Operator::ADD, Operator::SUB, Operator::MUL],
+            Difficulty::HARD => &[Operator::ADD, Operator::SUB, Operator::MUL, Operator::DIV],
+        }
+    }
+
+    /// 演算子の右側に置く数字の最大値
+    fn max_operand(self) -> usize {
+        match self {
+            Difficulty::EASY => 10,
+            Difficulty::NORMAL => 12,
+            Difficulty::HARD => 20,
+        }
+    }
+
+    /// 演算の回数
+    fn steps(self) -> usize {
+        match self {
+            Difficulty::EASY => 1,
+            Difficulty::NORMAL => 2,
+            Difficulty::HARD => 3,
+        }
+    }
+
+    /// 括弧が必要な式を作るかどうか
+    fn parentheses(self) -> bool {
+        matches!(self, Difficulty::HARD)
+    }
+}
+
+/// 演算子
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Operator {
+    ADD,
+    SUB,
+    MUL,
+    DIV,
+}
+
+impl Operator {
+    /// 掛け算と割り算は足し算と引き算より先に計算する
+    fn is_multiplicative(self) -> bool {
+        matches!(self, Operator::MUL | Operator::DIV)
+    }
+
+    fn symbol(self) -> char {
+        match self {
+            Operator::ADD => '+',
+            Operator::SUB => '-',
+            Operator::MUL => '×',
+            Operator::DIV => '÷',
+        }
+    }
+}
+
+/// 数式
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Expr {
+    NUMBER(usize),
+    /// 左辺は式、右辺は数字
+    BINARY {
+        operator: Operator,
+        lhs: Box<Expr>,
+        rhs: usize,
+    },
+}
+
+impl Expr {
+    /// 式の値 (割り切れない割り算がある場合はNone)
+    pub fn eval(&self) -> Option<usize> {
+        match self {
+            Expr::NUMBER(number) => Some(*number),
+            Expr::BINARY { operator, lhs, rhs } => {
+                let lhs = lhs.eval()?;
+                match operator {
+                    Operator::ADD => lhs.checked_add(*rhs),
+                    Operator::SUB => lhs.checked_sub(*rhs),
+                    Operator::MUL => lhs.checked_mul(*rhs),
+                    Operator::DIV if *rhs != 0 && lhs.is_multiple_of(*rhs) => Some(lhs / rhs),
+                    Operator::DIV => None,
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::NUMBER(number) => write!(f, "{number}"),
+            Expr::BINARY { operator, lhs, rhs } => {
+                // 左辺の足し算と引き算は掛け算と割り算より先に計算するため括弧で囲む
+                match lhs.as_ref() {
+                    Expr::BINARY {
+                        operator: inner, ..
+                    } if operator.is_multiplicative() && !inner.is_multiplicative() => {
+                        write!(f, "({lhs})")?
+                    }
+                    _ => write!(f, "{lhs}")?,
+                }
+                write!(f, " {} {rhs}", operator.symbol())
+            }
+        }
+    }
+}
+
+/// 抽選された数字を値とする数式を作る
+///
+/// 作られる数式はシードから決まるため、同じシードであれば同じ順番で作られる
+#[derive(Clone, Debug)]
+pub struct FormulaGenerator {
+    difficulty: Difficulty,
+    rng: StdRng,
+}
+
+impl FormulaGenerator {
+    pub fn new(difficulty: Difficulty, seed: u64) -> Self {
+        Self {
+            difficulty,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// 値がnumberになる数式を作る
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use game::formula::{Difficulty, FormulaGenerator};
+    /// let mut generator = FormulaGenerator::new(Difficulty::HARD, 0);
+    /// assert_eq!(generator.generate(42).eval(), Some(42));
+    /// ```
+    pub fn generate(&mut self, number: usize) -> Expr {
+        let steps = self.difficulty.steps();
+        self.build(number, steps, true)
+    }
+
+    /// 値がvalueになる数式を右辺から順に作る
+    /// additiveが偽の場合は左辺に足し算と引き算を置かない (括弧が要らないようにする)
+    fn build(&mut self, value: usize, steps: usize, additive: bool) -> Expr {
+        if steps == 0 {
+            return Expr::NUMBER(value);
+        }
+
+        let max = self.difficulty.max_operand();
+        let candidates: Vec<Operator> = self
+            .difficulty
+            .operators()
+            .iter()
+            .copied()
+            .filter(|operator| match operator {
+                Operator::ADD => additive && value > 1,
+                // 左辺が桁あふれする場合は他の演算子にする
+                Operator::SUB => additive && value.checked_add(max).is_some(),
+                Operator::MUL => divisors(value, max).next().is_some(),
+                Operator::DIV => value.checked_mul(max).is_some(),
+            })
+            .collect();
+
+        // 使える演算子がない場合はそのままの数字にする
+        let Some(&operator) = candidates.choose(&mut self.rng) else {
+            return Expr::NUMBER(value);
+        };
+
+        let (lhs, rhs) = match operator {
+            Operator::ADD => {
+                let rhs = self.rng.gen_range(1..=max.min(value - 1));
+                (value - rhs, rhs)
+            }
+            Operator::SUB => {
+                let rhs = self.rng.gen_range(1..=max);
+                (value + rhs, rhs)
+            }
+            Operator::MUL => {
+                let divisors: Vec<usize> = divisors(value, max).collect();
+                let rhs = *divisors.choose(&mut self.rng).unwrap_or(&1);
+                (value / rhs, rhs)
+            }
+            Operator::DIV => {
+                let rhs = self.rng.gen_range(2..=max);
+                (value * rhs, rhs)
+            }
+        };
+
+        let additive = !operator.is_multiplicative() || self.difficulty.parentheses();
+        Expr::BINARY {
+            operator,
+            lhs: Box::new(self.build(lhs, steps - 1, additive)),
+            rhs,
+        }
+    }
+}
+
+/// valueを割り切る2以上max以下の数
+fn divisors(value: usize, max: usize) -> impl Iterator<Item = usize> {
+    (2..=max.min(value / 2)).filter(move |d| value.is_multiple_of(*d))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Tokens = std::iter::Peekable<std::vec::IntoIter<String>>;
+
+    /// 表示された数式を計算する (掛け算と割り算を先に、同じ優先度は左から)
+    fn evaluate(expression: &str) -> Option<usize> {
+        fn expr(tokens: &mut Tokens) -> Option<usize> {
+            let mut value = term(tokens)?;
+            while let Some(op) = tokens.next_if(|t| t == "+" || t == "-") {
+                let rhs = term(tokens)?;
+                value = match op.as_str() {
+                    "+" => value.checked_add(rhs)?,
+                    _ => value.checked_sub(rhs)?,
+                };
+            }
+            Some(value)
+        }
+
+        fn term(tokens: &mut Tokens) -> Option<usize> {
+            let mut value = factor(tokens)?;
+            while let Some(op) = tokens.next_if(|t| t == "×" || t == "÷") {
+                let rhs = factor(tokens)?;
+                value = match op.as_str() {
+                    "×" => value.checked_mul(rhs)?,
+                    _ if value.is_multiple_of(rhs) => value / rhs,
+                    _ => return None,
+                };
+            }
+            Some(value)
+        }
+
+        fn factor(tokens: &mut Tokens) -> Option<usize> {
+            match tokens.next()?.as_str() {
+                "(" => {
+                    let value = expr(tokens)?;
+                    tokens.next_if(|t| t == ")")?;
+                    Some(value)
+                }
+                number => number.parse().ok(),
+            }
+        }
+
+        let tokens: Vec<String> = expression
+            .replace('(', "( ")
+            .replace(')', " )")
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+        let mut tokens = tokens.into_iter().peekable();
+        let value = expr(&mut tokens)?;
+
+        tokens.next().is_none().then_some(value)
+    }
+
+    #[test]
+    fn it_generates_formulas_with_right_values() {
+        for difficulty in [Difficulty::EASY, Difficulty::NORMAL, Difficulty::HARD] {
+            for seed in 0..20 {
+                let mut generator = FormulaGenerator::new(difficulty, seed);
+                for number in 1..=225 {
+                    let formula = generator.generate(number);
+                    let expression = formula.to_string();
+
+                    assert_eq!(formula.eval(), Some(number), "{expression}");
+                    assert_eq!(evaluate(&expression), Some(number), "{expression}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_does_not_overflow_with_large_numbers() {
+        for difficulty in [Difficulty::EASY, Difficulty::NORMAL, Difficulty::HARD] {
+            for seed in 0..20 {
+                let mut generator = FormulaGenerator::new(difficulty, seed);
+                for number in [usize::MAX, usize::MAX - 1, usize::MAX / 2 + 1] {
+                    let formula = generator.generate(number);
+                    let expression = formula.to_string();
+
+                    assert_eq!(formula.eval(), Some(number), "{expression}");
+                    assert_eq!(evaluate(&expression), Some(number), "{expression}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_follows_difficulty() {
+        let mut generator = FormulaGenerator::new(Difficulty::EASY, 0);
+        for number in 1..=75 {
+            let expression = generator.generate(number).to_string();
+            assert!(!expression.contains(['×', '÷', '(']), "{expression}");
+        }
+
+        let mut generator = FormulaGenerator::new(Difficulty::NORMAL, 0);
+        for number in 1..=75 {
+            let expression = generator.generate(number).to_string();
+            assert!(!expression.contains(['÷', '(']), "{expression}");
+        }
+
+        let mut generator = FormulaGenerator::new(Difficulty::HARD, 0);
+        let expressions: Vec<String> = (1..=75)
+            .map(|number| generator.generate(number).to_string())
+            .collect();
+        assert!(expressions.iter().any(|e| e.contains('(')));
+        assert!(expressions.iter().any(|e| e.contains('÷')));
+    }
+
+    #[test]
+    fn it_is_reproducible_with_same_seed() {
+        let mut a = FormulaGenerator::new(Difficulty::HARD, 7);
+        let mut b = FormulaGenerator::new(Difficulty::HARD, 7);
+
+        for number in 1..=75 {
+            assert_eq!(a.generate(number), b.generate(number));
+        }
+    }
+
+    #[test]
+    fn it_writes_parentheses_only_when_needed() {
+        let formula = Expr::BINARY {
+            operator: Operator::MUL,
+            lhs: Box::new(Expr::BINARY {
+                operator: Operator::ADD,
+                lhs: Box::new(Expr::NUMBER(3)),
+                rhs: 4,
+            }),
+            rhs: 5,
+        };
+        assert_eq!(formula.to_string(), "(3 + 4) × 5");
+
+        let formula = Expr::BINARY {
+            operator: Operator::ADD,
+            lhs: Box::new(Expr::BINARY {
+                operator: Operator::MUL,
+                lhs: Box::new(Expr::NUMBER(3)),
+                rhs: 7,
+            }),
+            rhs: 2,
+        };
+        assert_eq!(formula.to_string(), "3 × 7 + 2");
+    }
+}
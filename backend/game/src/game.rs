@@ -1,69 +1,381 @@
 use crate::config::{GameMode, GameSettings};
+use crate::draw::{DrawDetail, DrawResult, Drawer};
 use crate::errors::Error;
-use board::board::Board;
+use crate::ranking::{ranking, Bingo, Rank};
+use crate::rules::{registry, ModeContext, ModeRules};
+use crate::status::GameStatus;
+use board::board::{Board, OpenResult};
+use board::pattern::Pattern;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::Serialize;
 use serenity::all::UserId;
 use std::collections::HashMap;
-use std::time::SystemTime;
 
 #[derive(Serialize, Clone, Debug)]
 pub struct Game {
     pub(crate) id: u32,
+    /// 参加コード
+    pub(crate) code: String,
     pub(crate) host: UserId,
     pub(crate) mode: GameMode,
     pub(crate) settings: GameSettings,
+    pub(crate) status: GameStatus,
     participants: HashMap<UserId, Board>,
+    /// 抽選順と数字盤を決めるシード
+    /// 知られると今後の抽選を予測できるため、IDとは別に決めて公開しない
+    #[serde(skip)]
+    pub(crate) seed: u64,
+    drawer: Drawer,
+    /// ゲームモードの遊び方
+    #[serde(skip)]
+    rules: Box<dyn ModeRules>,
+    /// 認められたビンゴ (認められた順)
+    bingos: Vec<Bingo>,
+}
+
+/// 参加者に見せるゲームの情報
+#[derive(Serialize, Clone, Debug)]
+pub struct GameInfo {
+    pub id: u32,
+    pub code: String,
+    pub host: UserId,
+    pub mode: GameMode,
+    pub settings: GameSettings,
+    pub status: GameStatus,
+    /// 参加者の数
+    pub participants: usize,
+}
+
+/// 数字を開けた結果
+#[derive(Clone, PartialEq, Debug)]
+pub struct MarkResult {
+    pub opened: OpenResult,
+    /// 新しく認められたビンゴ
+    pub bingo: Option<Bingo>,
 }
 
 impl Game {
-    pub(crate) fn new(host: UserId, mode: GameMode, settings: GameSettings) -> (u32, Self) {
-        let id = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .subsec_nanos();
+    /// ゲームを作る
+    /// IDと参加コードは`GameManager`が重複しないように決める
+    /// `context.seed`は抽選順と数字盤を決める秘密の値で、IDから推測できないものを渡す
+    /// modeの遊び方を作れない場合や設定で数字盤を作れない場合はエラーになる
+    pub(crate) fn new(
+        id: u32,
+        code: String,
+        host: UserId,
+        mode: GameMode,
+        context: &ModeContext,
+    ) -> Result<Self, Error> {
+        let settings = context.settings.clone();
+        let seed = context.seed;
+        let rules = registry().create(mode, context)?;
+        let drawer = Drawer::new(seed, rules.pool(&settings));
 
-        (
+        Ok(Game {
             id,
-            Game {
-                id,
-                host,
-                mode,
-                settings,
-                participants: HashMap::new(),
-            },
-        )
+            code,
+            host,
+            mode,
+            settings,
+            status: GameStatus::default(),
+            participants: HashMap::new(),
+            seed,
+            drawer,
+            rules,
+            bingos: vec![],
+        })
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
     }
 
+    pub fn host(&self) -> UserId {
+        self.host
+    }
+
+    pub fn status(&self) -> GameStatus {
+        self.status
+    }
+
+    pub fn drawer(&self) -> &Drawer {
+        &self.drawer
+    }
+
+    pub fn has_participant(&self, user: &UserId) -> bool {
+        self.participants.contains_key(user)
+    }
+
+    /// 参加者の数字盤
+    pub fn board(&self, user: UserId) -> Result<&Board, Error> {
+        self.participants
+            .get(&user)
+            .ok_or(Error::NotParticipant { user })
+    }
+
+    pub fn info(&self) -> GameInfo {
+        GameInfo {
+            id: self.id,
+            code: self.code.clone(),
+            host: self.host,
+            mode: self.mode,
+            settings: self.settings.clone(),
+            status: self.status,
+            participants: self.participants.len(),
+        }
+    }
+
+    /// 認められたビンゴ (認められた順)
+    pub fn bingos(&self) -> &[Bingo] {
+        &self.bingos
+    }
+
+    /// 現時点での順位表
+    pub fn ranking(&self) -> Vec<Rank> {
+        ranking(&self.bingos)
+    }
+
+    /// ゲームの状態を遷移させる
+    pub(crate) fn transition(&mut self, to: GameStatus) -> Result<(), Error> {
+        if !self.status.can_transition_to(to) {
+            return Err(Error::InvalidTransition {
+                from: self.status,
+                to,
+            });
+        }
+
+        self.status = to;
+        Ok(())
+    }
+
+    fn ensure_status(&self, allowed: &[GameStatus]) -> Result<(), Error> {
+        if !allowed.contains(&self.status) {
+            return Err(Error::InvalidStatus {
+                status: self.status,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// 参加する (募集中のみ)
     pub(crate) fn join(&mut self, id: UserId) -> Result<Board, Error> {
+        if let Some(board) = self.participants.get(&id) {
+            return Ok(board.clone());
+        }
+
+        self.ensure_status(&[GameStatus::LOBBY])?;
+
         if let Some(max) = self.settings.max_player {
             if self.participants.len() == max {
                 return Err(Error::MaxPlayers);
             }
         }
 
-        if let Some(board) = self.participants.get(&id) {
-            return Ok(board.clone());
+        let board = self.rules.board(self.board_id(id), &self.settings)?;
+        self.participants.insert(id, board.clone());
+
+        Ok(board)
+    }
+
+    /// 参加者の数字盤のID
+    /// 数字盤のIDは本人に公開されるため、そこからゲームのシードを逆算できないようにする
+    fn board_id(&self, user: UserId) -> u64 {
+        StdRng::seed_from_u64(self.seed ^ user.get()).gen()
+    }
+
+    /// 参加者の数字盤の数字を開ける (抽選中と一時停止中のみ)
+    /// 抽選されていない数字は開けられない
+    pub(crate) fn mark(&mut self, user: UserId, number: usize) -> Result<MarkResult, Error> {
+        self.ensure_status(&[GameStatus::RUNNING, GameStatus::PAUSED])?;
+
+        let board = self
+            .participants
+            .get_mut(&user)
+            .ok_or(Error::NotParticipant { user })?;
+
+        self.rules.validate_mark(&self.drawer, number)?;
+
+        let opened = board.open(number)?;
+        let draw_count = self.drawer.history().len();
+        let bingo = self.award(user, &opened.new_bingo, &opened.new_patterns, draw_count);
+
+        Ok(MarkResult { opened, bingo })
+    }
+
+    /// 次の数字を抽選する (抽選中のみ)
+    /// `GameSettings::auto_open`が有効な場合は全参加者の数字盤も開ける
+    ///
+    /// 抽選の仕方はゲームモードの遊び方 (`ModeRules::draw_next`) で決まる。
+    /// 1回で複数の数字を抽選した場合は、結果は抽選した順に並ぶ。
+    pub(crate) fn draw_next(&mut self) -> Result<Vec<DrawResult>, Error> {
+        self.ensure_status(&[GameStatus::RUNNING])?;
+
+        let drawn = self.rules.draw_next(&mut self.drawer)?;
+        // 各抽選の時点での抽選回数 (同じ回に抽選された数字の分を除く)
+        let before = self.drawer.history().len() - drawn.len();
+
+        drawn
+            .into_iter()
+            .enumerate()
+            .map(|(i, drawn)| self.open_all(drawn.number, drawn.detail, before + i + 1))
+            .collect()
+    }
+
+    /// 指定した数字を抽選済みにする (抽選中のみ)
+    /// `GameSettings::auto_open`が有効な場合は全参加者の数字盤も開ける
+    pub(crate) fn draw(&mut self, number: usize) -> Result<DrawResult, Error> {
+        self.ensure_status(&[GameStatus::RUNNING])?;
+
+        let number = self.drawer.draw(number)?;
+        let detail = self.rules.detail(number);
+        self.open_all(number, detail, self.drawer.history().len())
+    }
+
+    /// draw_count回目に抽選されたnumberを全参加者の数字盤で開ける
+    fn open_all(
+        &mut self,
+        number: usize,
+        detail: Option<DrawDetail>,
+        draw_count: usize,
+    ) -> Result<DrawResult, Error> {
+        let label = self.rules.call_label(number);
+
+        if !self.settings.auto_open {
+            return Ok(DrawResult {
+                number,
+                detail,
+                label,
+                opened: HashMap::new(),
+                bingos: vec![],
+            });
         }
 
-        if let Ok(board) = Board::new(id.get() + u64::from(self.id), 5) {
-            self.participants.insert(id, board.clone());
-            Ok(board)
-        } else {
-            Err(Error::BoardGenerationError)
+        let opened = self
+            .participants
+            .iter_mut()
+            .map(|(&user, board)| Ok((user, board.open(number)?)))
+            .collect::<Result<HashMap<UserId, OpenResult>, Error>>()?;
+        let bingos = opened
+            .iter()
+            .filter_map(|(&user, result)| {
+                self.award(user, &result.new_bingo, &result.new_patterns, draw_count)
+            })
+            .collect();
+
+        Ok(DrawResult {
+            number,
+            detail,
+            label,
+            opened,
+            bingos,
+        })
+    }
+
+    /// 新しく揃ったラインがあればビンゴとして記録する
+    /// `GameSettings::multiple_bingo`が無効な場合は1人1回までしか認めない
+    fn award(
+        &mut self,
+        user: UserId,
+        lines: &[Vec<usize>],
+        patterns: &[Pattern],
+        draw_count: usize,
+    ) -> Option<Bingo> {
+        if lines.is_empty() {
+            return None;
+        }
+
+        if !self.settings.multiple_bingo && self.bingos.iter().any(|bingo| bingo.user == user) {
+            return None;
         }
+
+        let bingo = Bingo {
+            user,
+            draw_count,
+            lines: lines.to_vec(),
+            patterns: patterns.to_vec(),
+        };
+        self.bingos.push(bingo.clone());
+
+        Some(bingo)
+    }
+
+    /// ビンゴの申告を検証し、まだ記録されていないラインをビンゴとして記録する (抽選中と一時停止中のみ)
+    /// 揃っているラインが全て記録済みの場合は同じビンゴを繰り返し申告したものとして拒否する
+    pub(crate) fn claim_bingo(&mut self, user: UserId) -> Result<Bingo, Error> {
+        self.ensure_status(&[GameStatus::RUNNING, GameStatus::PAUSED])?;
+
+        let board = self
+            .participants
+            .get(&user)
+            .ok_or(Error::NotParticipant { user })?;
+        let lines = board.judge_bingo().ok_or(Error::NoBingo)?;
+        let patterns = board.judge_patterns();
+
+        let claimed: Vec<&Bingo> = self.bingos.iter().filter(|b| b.user == user).collect();
+        let lines: Vec<Vec<usize>> = lines
+            .into_iter()
+            .filter(|line| !claimed.iter().any(|b| b.lines.contains(line)))
+            .collect();
+        let patterns: Vec<Pattern> = patterns
+            .into_iter()
+            .filter(|pattern| !claimed.iter().any(|b| b.patterns.contains(pattern)))
+            .collect();
+
+        let draw_count = self.drawer.history().len();
+        self.award(user, &lines, &patterns, draw_count)
+            .ok_or(Error::AlreadyClaimed { user })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::asset::Asset;
+    use crate::asset::AssetManifest;
+    use crate::color::ColorFormat;
+    use crate::numeral::NumeralRegistry;
+    use crate::symbol::Catalog;
+    use board::board::{BoardState, OpenStatus};
+    use board::cell::CellKind;
+    use board::dice::{DiceMode, DiceSet};
+    use board::tile::Tile;
+
+    const GAME_ID: u32 = 42;
+    const GAME_CODE: &str = "ABC234";
+    const GAME_SEED: u64 = 7;
+
+    fn create(host: UserId, mode: GameMode, settings: GameSettings) -> Game {
+        create_with(host, mode, settings, &AssetManifest::default())
+    }
+
+    fn create_with(
+        host: UserId,
+        mode: GameMode,
+        settings: GameSettings,
+        assets: &AssetManifest,
+    ) -> Game {
+        let context = ModeContext {
+            settings: &settings,
+            seed: GAME_SEED,
+            assets,
+            numerals: &NumeralRegistry::builtin(),
+        };
+        Game::new(GAME_ID, GAME_CODE.to_string(), host, mode, &context).unwrap()
+    }
 
     #[test]
     fn it_can_create_game() {
         let user = UserId::default();
-        let (id, game) = Game::new(user, GameMode::NORMAL, GameSettings::default());
+        let game = create(user, GameMode::NORMAL, GameSettings::default());
 
-        assert_eq!(id, game.id);
+        assert_eq!(game.id(), GAME_ID);
+        assert_eq!(game.code(), GAME_CODE);
         assert_eq!(user, game.host);
     }
 
@@ -71,10 +383,723 @@ mod tests {
     fn it_can_join_game() {
         let user = UserId::default();
 
-        let (_, mut game) = Game::new(user, GameMode::NORMAL, GameSettings::default());
+        let mut game = create(user, GameMode::NORMAL, GameSettings::default());
         let board = game.join(user).unwrap();
 
-        assert_eq!(user.get() + u64::from(game.id), board.id);
+        assert_eq!(game.board_id(user), board.id);
         assert_eq!(board, game.join(user).unwrap());
+        assert!(game.has_participant(&user));
+    }
+
+    #[test]
+    fn it_draws_by_secret_seed() {
+        let user = UserId::new(1);
+        let game = |id, seed| {
+            let context = ModeContext {
+                settings: &GameSettings::default(),
+                seed,
+                assets: &AssetManifest::default(),
+                numerals: &NumeralRegistry::builtin(),
+            };
+            let mut game =
+                Game::new(id, GAME_CODE.to_string(), user, GameMode::NORMAL, &context).unwrap();
+            game.transition(GameStatus::RUNNING).unwrap();
+            for _ in 0..10 {
+                game.draw_next().unwrap();
+            }
+            game
+        };
+
+        // 抽選順はIDではなくシードで決まる
+        let a = game(1, GAME_SEED);
+        assert_eq!(a.drawer.history(), game(2, GAME_SEED).drawer.history());
+        assert_ne!(a.drawer.history(), game(1, GAME_SEED + 1).drawer.history());
+
+        let json = serde_json::to_value(&a).unwrap();
+        assert!(json.get("seed").is_none());
+    }
+
+    #[test]
+    fn it_shows_info_and_boards() {
+        let (host, player) = (UserId::new(1), UserId::new(2));
+        let mut game = create(host, GameMode::BINARY, GameSettings::default());
+        let board = game.join(player).unwrap();
+
+        let info = game.info();
+        assert_eq!(info.code, GAME_CODE);
+        assert_eq!(info.host, host);
+        assert_eq!(info.status, GameStatus::LOBBY);
+        assert_eq!(info.participants, 1);
+
+        assert_eq!(game.board(player), Ok(&board));
+        assert_eq!(game.board(host), Err(Error::NotParticipant { user: host }));
+    }
+
+    #[test]
+    fn it_awards_bingo_for_configured_pattern() {
+        let user = UserId::default();
+        let settings = GameSettings {
+            auto_open: true,
+            patterns: vec![Pattern::CORNERS],
+            ..Default::default()
+        };
+
+        let mut game = create(user, GameMode::NORMAL, settings);
+        let board = game.join(user).unwrap();
+        game.transition(GameStatus::RUNNING).unwrap();
+
+        let numbers = board.numbers();
+        let corners = [numbers[0][0], numbers[0][4], numbers[4][0], numbers[4][4]];
+        for number in corners {
+            game.draw(number).unwrap();
+        }
+
+        assert_eq!(game.bingos().len(), 1);
+        assert_eq!(game.bingos()[0].patterns, vec![Pattern::CORNERS]);
+        assert_eq!(game.bingos()[0].lines, vec![corners.to_vec()]);
+    }
+
+    #[test]
+    fn it_uses_board_size_and_range_from_settings() {
+        let user = UserId::default();
+        let settings = GameSettings {
+            board_size: 7,
+            ..Default::default()
+        };
+
+        let mut game = create(user, GameMode::NORMAL, settings);
+        let board = game.join(user).unwrap();
+
+        assert_eq!(board.size, 7);
+        assert_eq!(board.numbers().len(), 7);
+        assert_eq!(game.drawer().remaining(), 105);
+    }
+
+    fn auto_open() -> GameSettings {
+        GameSettings {
+            auto_open: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn it_can_mark_and_claim_bingo() {
+        let user = UserId::new(1);
+
+        let mut game = create(user, GameMode::NORMAL, GameSettings::default());
+        let board = game.join(user).unwrap();
+        game.transition(GameStatus::RUNNING).unwrap();
+        assert_eq!(game.claim_bingo(user), Err(Error::NoBingo));
+
+        let row = board.numbers()[0].clone();
+        for &number in &row {
+            let result = game.draw(number).unwrap();
+            assert!(result.opened.is_empty());
+        }
+        assert!(game.participants[&user].opened().is_empty());
+
+        for &number in &row[..row.len() - 1] {
+            game.mark(user, number).unwrap();
+        }
+        assert_eq!(
+            game.mark(user, row[row.len() - 1]),
+            Ok(MarkResult {
+                opened: OpenResult {
+                    status: OpenStatus::OPENED,
+                    state: BoardState::BINGO(vec![row.clone()]),
+                    new_bingo: vec![row.clone()],
+                    new_reach: vec![],
+                    reach_count: 0,
+                    new_patterns: vec![Pattern::LINE],
+                },
+                bingo: Some(Bingo {
+                    user,
+                    draw_count: row.len(),
+                    lines: vec![row.clone()],
+                    patterns: vec![Pattern::LINE],
+                }),
+            })
+        );
+        // 開けたときに記録されたビンゴは申告し直せない
+        assert_eq!(game.claim_bingo(user), Err(Error::AlreadyClaimed { user }));
+        assert_eq!(game.bingos.len(), 1);
+    }
+
+    #[test]
+    fn it_records_claimed_bingo_once() {
+        let (host, player) = (UserId::new(1), UserId::new(2));
+
+        let mut game = create(host, GameMode::NORMAL, GameSettings::default());
+        let board = game.join(player).unwrap();
+        game.transition(GameStatus::RUNNING).unwrap();
+
+        // 記録されずに揃ったライン
+        let row = board.numbers()[0].clone();
+        for &number in &row {
+            game.draw(number).unwrap();
+            game.participants
+                .get_mut(&player)
+                .unwrap()
+                .open(number)
+                .unwrap();
+        }
+
+        assert_eq!(
+            game.claim_bingo(player),
+            Ok(Bingo {
+                user: player,
+                draw_count: row.len(),
+                lines: vec![row],
+                patterns: vec![Pattern::LINE],
+            })
+        );
+        assert_eq!(game.ranking().len(), 1);
+        assert_eq!(
+            game.claim_bingo(player),
+            Err(Error::AlreadyClaimed { user: player })
+        );
+        assert_eq!(game.bingos.len(), 1);
+    }
+
+    #[test]
+    fn it_opens_drawn_number_on_every_board() {
+        let (host, player) = (UserId::new(1), UserId::new(2));
+
+        let mut game = create(host, GameMode::NORMAL, auto_open());
+        game.join(host).unwrap();
+        game.join(player).unwrap();
+        game.transition(GameStatus::RUNNING).unwrap();
+
+        game.draw_next().unwrap();
+        let result = game.draw_next().unwrap().remove(0);
+        assert!((1..=75).contains(&result.number));
+        assert_eq!(result.detail, None);
+        assert_eq!(result.opened.len(), 2);
+        assert_eq!(game.drawer().history().len(), 2);
+        for board in game.participants.values() {
+            // 数字盤に書かれていない数字は開けられない
+            let on_board: Vec<usize> = game
+                .drawer()
+                .history()
+                .iter()
+                .copied()
+                .filter(|number| board.numbers().iter().flatten().any(|x| x == number))
+                .collect();
+            assert_eq!(board.opened(), on_board);
+        }
+
+        assert_eq!(
+            game.draw(result.number).unwrap_err(),
+            Error::AlreadyDrawn {
+                number: result.number
+            }
+        );
+    }
+
+    #[test]
+    fn it_draws_by_slot_in_slot_mode() {
+        let user = UserId::new(1);
+        let mut game = create(user, GameMode::SLOT, GameSettings::default());
+        game.transition(GameStatus::RUNNING).unwrap();
+
+        while game.drawer().remaining() > 0 {
+            let results = game.draw_next().unwrap();
+
+            let Some(DrawDetail::SLOT(spin)) = &results[0].detail else {
+                panic!("slot result is missing");
+            };
+            assert!(results.len() <= spin.outcome.draws());
+            assert!(results[1..].iter().all(|result| result.detail.is_none()));
+        }
+
+        let mut history = game.drawer().history().to_vec();
+        history.sort();
+        assert_eq!(history, (1..=75).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn it_draws_by_dice_in_dice_mode() {
+        let (host, player) = (UserId::new(1), UserId::new(2));
+        let mut game = create(host, GameMode::DICE, auto_open());
+        let board = game.join(player).unwrap();
+        game.transition(GameStatus::RUNNING).unwrap();
+
+        // 3d20の合計なので3から60の全てが抽選される
+        assert!(board.numbers().iter().flatten().all(|&n| n <= 60));
+        while game.drawer().remaining() > 0 {
+            let result = game.draw_next().unwrap().remove(0);
+
+            let Some(DrawDetail::DICE(roll)) = &result.detail else {
+                panic!("dice result is missing");
+            };
+            assert_eq!(roll.faces.len(), 3);
+            assert_eq!(roll.faces.iter().sum::<usize>(), result.number);
+        }
+        assert_eq!(game.draw_next().unwrap_err(), Error::NoNumbersLeft);
+
+        let mut history = game.drawer().history().to_vec();
+        history.sort();
+        assert_eq!(history, (3..=60).collect::<Vec<_>>());
+        assert_eq!(game.participants[&player].opened().len(), 24);
+    }
+
+    #[test]
+    fn it_drains_large_dice_pool() {
+        let host = UserId::new(1);
+        let dice = DiceSet {
+            dice: vec![100; 8],
+            mode: DiceMode::SUM,
+        };
+        let settings = GameSettings {
+            dice: Some(dice),
+            ..Default::default()
+        };
+        let mut game = create(host, GameMode::DICE, settings);
+        game.transition(GameStatus::RUNNING).unwrap();
+
+        // 両端の数字は1e-16程度の確率でしか出ないが、振り直さないので必ず抽選し終わる
+        while game.drawer().remaining() > 0 {
+            let result = game.draw_next().unwrap().remove(0);
+            let Some(DrawDetail::DICE(roll)) = &result.detail else {
+                panic!("dice result is missing");
+            };
+            assert!(roll.faces.iter().all(|face| (1..=100).contains(face)));
+            assert_eq!(roll.faces.iter().sum::<usize>(), result.number);
+        }
+
+        let mut history = game.drawer().history().to_vec();
+        history.sort();
+        assert_eq!(history, (8..=800).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn it_draws_tiles_in_mahjong_mode() {
+        let (host, player) = (UserId::new(1), UserId::new(2));
+        let mut game = create(host, GameMode::MAHJONG, auto_open());
+        let board = game.join(player).unwrap();
+        game.transition(GameStatus::RUNNING).unwrap();
+
+        assert_eq!(board.kind, CellKind::TILE);
+        let mut wall = 136;
+        while game.drawer().remaining() > 0 {
+            let result = game.draw_next().unwrap().remove(0);
+
+            let Some(DrawDetail::MAHJONG { tile, wall: left }) = result.detail else {
+                panic!("mahjong result is missing");
+            };
+            assert_eq!(tile.code(), result.number);
+            assert!(left < wall);
+            wall = left;
+        }
+        assert_eq!(game.draw_next().unwrap_err(), Error::NoNumbersLeft);
+
+        let mut history = game.drawer().history().to_vec();
+        history.sort();
+        assert_eq!(history, (1..=Tile::KINDS).collect::<Vec<_>>());
+        assert_eq!(game.participants[&player].opened().len(), 24);
+    }
+
+    #[test]
+    fn it_labels_board_and_calls_in_hexadecimal_mode() {
+        let user = UserId::new(1);
+        let mut game = create(user, GameMode::HEXADECIMAL, GameSettings::default());
+        let board = game.join(user).unwrap();
+        game.transition(GameStatus::RUNNING).unwrap();
+
+        let labels = board.labels.as_ref().unwrap();
+        assert_eq!(labels[0][0], format!("0x{:02X}", board.numbers()[0][0]));
+
+        let result = game.draw_next().unwrap().remove(0);
+        assert_eq!(result.label, Some(format!("0x{:02X}", result.number)));
+        // 判定は数字のまま行う
+        assert!(game.mark(user, result.number).is_ok());
+    }
+
+    #[test]
+    fn it_announces_formula_in_formula_mode() {
+        let user = UserId::new(1);
+        let mut game = create(user, GameMode::FORMULA, GameSettings::default());
+        game.transition(GameStatus::RUNNING).unwrap();
+
+        let result = game.draw_next().unwrap().remove(0);
+        let Some(DrawDetail::FORMULA { expression }) = &result.detail else {
+            panic!("formula is missing");
+        };
+        assert!(expression.contains(['+', '-', '×']));
+
+        let number = (1..=75).find(|&n| !game.drawer().is_drawn(n)).unwrap();
+        let result = game.draw(number).unwrap();
+        assert!(matches!(result.detail, Some(DrawDetail::FORMULA { .. })));
+    }
+
+    #[test]
+    fn it_shows_symbols_in_emoji_mode() {
+        let user = UserId::new(1);
+        let mut game = create(user, GameMode::EMOJI, GameSettings::default());
+        let board = game.join(user).unwrap();
+        game.transition(GameStatus::RUNNING).unwrap();
+
+        let catalog = Catalog::EMOJI;
+        let labels = board.labels.as_ref().unwrap();
+        assert_eq!(labels[0][0], catalog.symbol(board.numbers()[0][0]).unwrap());
+
+        let result = game.draw_next().unwrap().remove(0);
+        assert!(result.number <= catalog.len());
+        assert_eq!(result.label, catalog.symbol(result.number));
+    }
+
+    #[test]
+    fn it_announces_color_codes_in_color_mode() {
+        let user = UserId::new(1);
+        let settings = GameSettings {
+            color_format: ColorFormat::HSL,
+            ..Default::default()
+        };
+        let mut game = create(user, GameMode::COLOR, settings);
+        let board = game.join(user).unwrap();
+        game.transition(GameStatus::RUNNING).unwrap();
+
+        let labels = board.labels.as_ref().unwrap();
+        assert!(labels
+            .iter()
+            .flatten()
+            .all(|l| l.starts_with('#') || l == "FREE"));
+
+        let result = game.draw_next().unwrap().remove(0);
+        assert!(result.label.unwrap().starts_with("hsl("));
+    }
+
+    #[test]
+    fn it_announces_numerals_in_foreign_mode() {
+        let user = UserId::new(1);
+        let settings = GameSettings {
+            numeral: "ROMAN".to_string(),
+            ..Default::default()
+        };
+        let mut game = create(user, GameMode::FOREIGN, settings);
+        let board = game.join(user).unwrap();
+        game.transition(GameStatus::RUNNING).unwrap();
+
+        // マスは数字のまま
+        assert_eq!(board.labels, None);
+
+        let result = game.draw_next().unwrap().remove(0);
+        let roman = NumeralRegistry::builtin().get("ROMAN").unwrap();
+        assert_eq!(roman.parse(&result.label.unwrap()), Some(result.number));
+    }
+
+    #[test]
+    fn it_assigns_assets_in_celebrity_mode() {
+        let assets = AssetManifest {
+            celebrities: (0..80)
+                .map(|i| Asset {
+                    id: format!("celebrity-{i}"),
+                    name: format!("Celebrity {i}"),
+                    image: Some(format!("celebrity/{i}.png")),
+                    family: None,
+                })
+                .collect(),
+            fonts: vec![],
+        };
+        let user = UserId::new(1);
+        let mut game = create_with(user, GameMode::CELEBRITY, GameSettings::default(), &assets);
+        let board = game.join(user).unwrap();
+        game.transition(GameStatus::RUNNING).unwrap();
+
+        let labels = board.labels.as_ref().unwrap();
+        assert!(labels[0][0].starts_with("celebrity-"));
+
+        let result = game.draw_next().unwrap().remove(0);
+        let Some(DrawDetail::ASSET(asset)) = &result.detail else {
+            panic!("asset is missing");
+        };
+        assert_eq!(result.label.as_ref(), Some(&asset.id));
+
+        // 同じ数字には数字盤でも抽選でも同じ素材が割り当てられる
+        let number = board.numbers()[0][0];
+        if !game.drawer().is_drawn(number) {
+            let result = game.draw(number).unwrap();
+            assert_eq!(result.label.as_ref(), Some(&labels[0][0]));
+        }
+    }
+
+    #[test]
+    fn it_draws_same_numbers_with_same_slot_seed() {
+        let user = UserId::new(1);
+        let mut a = create(user, GameMode::SLOT, GameSettings::default());
+        let mut b = a.clone();
+        for game in [&mut a, &mut b] {
+            game.transition(GameStatus::RUNNING).unwrap();
+            for _ in 0..10 {
+                game.draw_next().unwrap();
+            }
+        }
+
+        assert_eq!(a.drawer().history(), b.drawer().history());
+    }
+
+    #[test]
+    fn it_reports_bingo_after_draws() {
+        let user = UserId::new(1);
+
+        let mut game = create(user, GameMode::NORMAL, auto_open());
+        let board = game.join(user).unwrap();
+        game.transition(GameStatus::RUNNING).unwrap();
+
+        let row = board.numbers()[0].clone();
+        for &number in &row[..row.len() - 1] {
+            game.draw(number).unwrap();
+        }
+        assert_eq!(
+            game.draw(row[row.len() - 1]).unwrap().opened[&user].state,
+            BoardState::BINGO(vec![row])
+        );
+    }
+
+    /// 1行目を揃えた後に1列目を揃える
+    fn complete_row_then_column(game: &mut Game, board: &Board) -> (DrawResult, DrawResult) {
+        let row = &board.numbers()[0];
+        for &number in &row[..row.len() - 1] {
+            game.draw(number).unwrap();
+        }
+        let first = game.draw(row[row.len() - 1]).unwrap();
+
+        let column: Vec<usize> = board.numbers().iter().map(|row| row[0]).collect();
+        for &number in &column[1..column.len() - 1] {
+            game.draw(number).unwrap();
+        }
+        let second = game.draw(column[column.len() - 1]).unwrap();
+
+        (first, second)
+    }
+
+    #[test]
+    fn it_awards_single_bingo_per_player() {
+        let user = UserId::new(1);
+
+        let mut game = create(user, GameMode::NORMAL, auto_open());
+        let board = game.join(user).unwrap();
+        game.transition(GameStatus::RUNNING).unwrap();
+
+        let (first, second) = complete_row_then_column(&mut game, &board);
+        assert_eq!(
+            first.bingos,
+            vec![Bingo {
+                user,
+                draw_count: 5,
+                lines: vec![board.numbers()[0].clone()],
+                patterns: vec![Pattern::LINE],
+            }]
+        );
+        assert_eq!(second.opened[&user].new_bingo.len(), 1);
+        assert!(second.bingos.is_empty());
+        assert_eq!(game.bingos().len(), 1);
+    }
+
+    #[test]
+    fn it_awards_multiple_bingos() {
+        let user = UserId::new(1);
+
+        let settings = GameSettings {
+            multiple_bingo: true,
+            ..auto_open()
+        };
+        let mut game = create(user, GameMode::NORMAL, settings);
+        let board = game.join(user).unwrap();
+        game.transition(GameStatus::RUNNING).unwrap();
+
+        let (first, second) = complete_row_then_column(&mut game, &board);
+        assert_eq!(first.bingos.len(), 1);
+        assert_eq!(
+            second.bingos,
+            vec![Bingo {
+                user,
+                draw_count: 9,
+                lines: vec![board.numbers().iter().map(|row| row[0]).collect()],
+                patterns: vec![Pattern::LINE],
+            }]
+        );
+        assert_eq!(game.ranking()[0].bingo_count, 2);
+    }
+
+    #[test]
+    fn it_ranks_players_sharing_same_draw() {
+        let (host, player, late) = (UserId::new(1), UserId::new(2), UserId::new(3));
+
+        let mut game = create(host, GameMode::NORMAL, GameSettings::default());
+        let host_board = game.join(host).unwrap();
+        let player_board = game.join(player).unwrap();
+        let late_board = game.join(late).unwrap();
+        game.transition(GameStatus::RUNNING).unwrap();
+
+        let rows =
+            [&host_board, &player_board, &late_board].map(|board| board.numbers()[0].clone());
+        for &number in rows.iter().flatten() {
+            // 他の参加者と同じ数字は既に抽選済み
+            let _ = game.draw(number);
+        }
+        let draw_count = game.drawer().history().len();
+
+        for &number in &rows[0] {
+            game.mark(host, number).unwrap();
+        }
+        for &number in &rows[1] {
+            game.mark(player, number).unwrap();
+        }
+        game.draw_next().unwrap();
+        for &number in &rows[2] {
+            game.mark(late, number).unwrap();
+        }
+
+        assert_eq!(
+            game.ranking(),
+            vec![
+                Rank {
+                    rank: 1,
+                    user: host,
+                    draw_count,
+                    bingo_count: 1,
+                },
+                Rank {
+                    rank: 1,
+                    user: player,
+                    draw_count,
+                    bingo_count: 1,
+                },
+                Rank {
+                    rank: 3,
+                    user: late,
+                    draw_count: draw_count + 1,
+                    bingo_count: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_cannot_mark_without_joining() {
+        let user = UserId::new(1);
+        let mut game = create(user, GameMode::NORMAL, GameSettings::default());
+        game.transition(GameStatus::RUNNING).unwrap();
+
+        assert_eq!(game.mark(user, 1), Err(Error::NotParticipant { user }));
+        assert_eq!(game.claim_bingo(user), Err(Error::NotParticipant { user }));
+    }
+
+    #[test]
+    fn it_cannot_mark_undrawn_number() {
+        let user = UserId::new(1);
+
+        let mut game = create(user, GameMode::NORMAL, GameSettings::default());
+        let board = game.join(user).unwrap();
+        game.transition(GameStatus::RUNNING).unwrap();
+
+        // 数字盤に書かれているが抽選されていない数字
+        let number = board.numbers()[0][0];
+        assert_eq!(game.mark(user, number), Err(Error::NotDrawn { number }));
+        assert!(game.participants[&user].opened().is_empty());
+
+        // 抽選されていない数字を開けてビンゴを申告しようとする
+        for &number in &board.numbers()[0] {
+            assert_eq!(game.mark(user, number), Err(Error::NotDrawn { number }));
+        }
+        assert_eq!(game.claim_bingo(user), Err(Error::NoBingo));
+
+        game.draw(number).unwrap();
+        assert_eq!(
+            game.mark(user, number).unwrap().opened.state,
+            BoardState::NONE
+        );
+    }
+
+    #[test]
+    fn it_cannot_mark_undrawn_number_with_auto_open() {
+        let user = UserId::new(1);
+
+        let mut game = create(user, GameMode::NORMAL, auto_open());
+        let board = game.join(user).unwrap();
+        game.transition(GameStatus::RUNNING).unwrap();
+
+        let number = board.numbers()[0][0];
+        assert_eq!(game.mark(user, number), Err(Error::NotDrawn { number }));
+    }
+
+    #[test]
+    fn it_can_join_only_in_lobby() {
+        let (host, player) = (UserId::new(1), UserId::new(2));
+
+        let mut game = create(host, GameMode::NORMAL, GameSettings::default());
+        let board = game.join(host).unwrap();
+        game.transition(GameStatus::RUNNING).unwrap();
+
+        assert_eq!(
+            game.join(player),
+            Err(Error::InvalidStatus {
+                status: GameStatus::RUNNING
+            })
+        );
+        // 既に参加している場合は自分の数字盤を取得できる
+        assert_eq!(game.join(host), Ok(board));
+    }
+
+    #[test]
+    fn it_can_draw_only_while_running() {
+        let user = UserId::new(1);
+        let mut game = create(user, GameMode::NORMAL, GameSettings::default());
+        let board = game.join(user).unwrap();
+
+        let lobby = Error::InvalidStatus {
+            status: GameStatus::LOBBY,
+        };
+        assert_eq!(game.draw_next().unwrap_err(), lobby);
+        assert_eq!(game.mark(user, 1).unwrap_err(), lobby);
+
+        game.transition(GameStatus::RUNNING).unwrap();
+        let number = board.numbers()[0][0];
+        game.draw(number).unwrap();
+
+        game.transition(GameStatus::PAUSED).unwrap();
+        assert_eq!(
+            game.draw_next().unwrap_err(),
+            Error::InvalidStatus {
+                status: GameStatus::PAUSED
+            }
+        );
+        // 一時停止中も抽選済みの数字は開けられる
+        assert!(game.mark(user, number).is_ok());
+
+        game.transition(GameStatus::RUNNING).unwrap();
+        assert!(game.draw_next().is_ok());
+
+        game.transition(GameStatus::FINISHED).unwrap();
+        let finished = Error::InvalidStatus {
+            status: GameStatus::FINISHED,
+        };
+        assert_eq!(game.draw_next().unwrap_err(), finished);
+        assert_eq!(game.claim_bingo(user).unwrap_err(), finished);
+    }
+
+    #[test]
+    fn it_rejects_invalid_transition() {
+        let user = UserId::new(1);
+        let mut game = create(user, GameMode::NORMAL, GameSettings::default());
+
+        assert_eq!(
+            game.transition(GameStatus::FINISHED),
+            Err(Error::InvalidTransition {
+                from: GameStatus::LOBBY,
+                to: GameStatus::FINISHED
+            })
+        );
+        assert_eq!(game.status(), GameStatus::LOBBY);
+
+        game.transition(GameStatus::CANCELLED).unwrap();
+        assert_eq!(
+            game.transition(GameStatus::RUNNING),
+            Err(Error::InvalidTransition {
+                from: GameStatus::CANCELLED,
+                to: GameStatus::RUNNING
+            })
+        );
     }
 }
@@ -1,4 +1,19 @@
+pub mod asset;
+pub mod code;
+pub mod color;
 pub mod config;
+pub mod dice;
+pub mod draw;
+pub mod encoding;
 pub mod errors;
+pub mod event;
+pub mod formula;
 pub mod game;
+pub mod mahjong;
 pub mod manager;
+pub mod numeral;
+pub mod ranking;
+pub mod rules;
+pub mod slot;
+pub mod status;
+pub mod symbol;
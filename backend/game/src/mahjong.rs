@@ -0,0 +1,52 @@
+use board::tile::{self, Tile};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+/// 山 (136枚の牌をシャッフルしたもの)
+///
+/// 牌の並びはシードから決まるため、同じシードであれば同じ順番で引かれる
+#[derive(Clone, Debug)]
+pub struct Wall {
+    /// まだ引かれていない牌 (末尾から引かれる)
+    tiles: Vec<Tile>,
+}
+
+impl Wall {
+    pub fn new(seed: u64) -> Self {
+        let mut tiles: Vec<Tile> = Tile::all().flat_map(|tile| [tile; tile::COPIES]).collect();
+        tiles.shuffle(&mut StdRng::seed_from_u64(seed));
+
+        Self { tiles }
+    }
+
+    /// 牌を1枚引く
+    pub fn draw(&mut self) -> Option<Tile> {
+        self.tiles.pop()
+    }
+
+    /// 山に残っている牌の数
+    pub fn remaining(&self) -> usize {
+        self.tiles.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn it_holds_four_copies_of_each_tile() {
+        let mut wall = Wall::new(0);
+        assert_eq!(wall.remaining(), 136);
+
+        let mut counts: HashMap<Tile, usize> = HashMap::new();
+        while let Some(tile) = wall.draw() {
+            *counts.entry(tile).or_default() += 1;
+        }
+
+        assert_eq!(counts.len(), Tile::KINDS);
+        assert!(counts.values().all(|&count| count == tile::COPIES));
+    }
+}
@@ -1,13 +1,43 @@
+use crate::asset::AssetManifest;
+use crate::code;
 use crate::config::{GameMode, GameSettings};
+use crate::draw::DrawResult;
 use crate::errors::Error;
-use crate::game::Game;
+use crate::game::{Game, MarkResult};
+use crate::numeral::{Numeral, NumeralRegistry};
+use crate::ranking::{Bingo, Rank};
+use crate::rules::ModeContext;
+use crate::status::GameStatus;
 use board::board::Board;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serenity::all::UserId;
 use std::collections::HashMap;
+use std::sync::Arc;
 
-#[derive(Default, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub struct GameManager {
     games: HashMap<u32, Game>,
+    /// 参加コードからゲームIDへの対応
+    codes: HashMap<String, u32>,
+    /// 著名人モードとフォントモードで使う素材
+    assets: Arc<AssetManifest>,
+    /// 異国語数字モードで使える書き表し方
+    numerals: Arc<NumeralRegistry>,
+    /// ゲームID、参加コードとゲームのシードを作る乱数 (推測されないようにOSの乱数で初期化する)
+    rng: StdRng,
+}
+
+impl Default for GameManager {
+    fn default() -> Self {
+        Self {
+            games: HashMap::new(),
+            codes: HashMap::new(),
+            assets: Arc::default(),
+            numerals: Arc::new(NumeralRegistry::builtin()),
+            rng: StdRng::from_entropy(),
+        }
+    }
 }
 
 impl GameManager {
@@ -15,6 +45,25 @@ impl GameManager {
         Self::default()
     }
 
+    /// assetsの素材を使う`GameManager`
+    pub fn with_assets(assets: AssetManifest) -> Self {
+        Self {
+            assets: Arc::new(assets),
+            ..Self::default()
+        }
+    }
+
+    /// 素材は大きいため、cloneせずに共有できるよう`Arc`のまま返す
+    pub fn assets(&self) -> &Arc<AssetManifest> {
+        &self.assets
+    }
+
+    /// 異国語数字モードで使える書き表し方を登録する (同じ名前の場合は置き換える)
+    /// 登録した後に作られたゲームから使える
+    pub fn register_numeral(&mut self, name: &str, numeral: impl Numeral + 'static) {
+        Arc::make_mut(&mut self.numerals).register(name, numeral);
+    }
+
     pub fn create_game(
         &mut self,
         host: UserId,
@@ -23,7 +72,7 @@ impl GameManager {
     ) -> Result<Game, Error> {
         // Host cannot create a game when they have ongoing games
         for game in self.games.values() {
-            if game.host == host {
+            if game.host == host && !game.status.is_over() {
                 return Err(Error::OngoingGame {
                     host,
                     game_id: game.id,
@@ -31,19 +80,128 @@ impl GameManager {
             }
         }
 
-        let (id, game) = Game::new(host, mode, settings);
+        let id = self.allocate_id();
+        let code = self.allocate_code();
+        let context = ModeContext {
+            settings: &settings,
+            seed: self.rng.gen(),
+            assets: &self.assets,
+            numerals: &self.numerals,
+        };
+        let game = Game::new(id, code.clone(), host, mode, &context)?;
 
         self.games.insert(id, game.clone());
+        self.codes.insert(code, id);
 
         Ok(game)
     }
 
+    /// 他のゲームと重複しないゲームIDを作る
+    fn allocate_id(&mut self) -> u32 {
+        loop {
+            let id = self.rng.gen();
+            if !self.games.contains_key(&id) {
+                return id;
+            }
+        }
+    }
+
+    /// 他のゲームと重複しない参加コードを作る
+    fn allocate_code(&mut self) -> String {
+        loop {
+            let code = code::generate(&mut self.rng);
+            if !self.codes.contains_key(&code) {
+                return code;
+            }
+        }
+    }
+
+    pub fn games(&self) -> Vec<&Game> {
+        self.games.values().collect()
+    }
+
+    pub fn game(&self, game_id: &u32) -> Result<&Game, Error> {
+        self.games
+            .get(game_id)
+            .ok_or(Error::NotFound { game_id: *game_id })
+    }
+
+    /// 参加コードからゲームを探す
+    /// 小文字や区切りのハイフンが含まれていてもよい
+    pub fn game_by_code(&self, code: &str) -> Result<&Game, Error> {
+        code::normalize(code)
+            .and_then(|code| self.codes.get(&code))
+            .and_then(|id| self.games.get(id))
+            .ok_or_else(|| Error::UnknownCode {
+                code: code.to_string(),
+            })
+    }
+
+    /// 参加者の数字盤 (開けたマスを含む)
+    pub fn board(&self, game_id: &u32, user_id: UserId) -> Result<&Board, Error> {
+        self.game(game_id)?.board(user_id)
+    }
+
     pub fn join_game(&mut self, game_id: &u32, user_id: UserId) -> Result<Board, Error> {
-        if let Some(game) = self.games.get_mut(game_id) {
-            Ok(game.join(user_id)?)
-        } else {
-            Err(Error::NotFound { game_id: *game_id })
+        self.game_mut(game_id)?.join(user_id)
+    }
+
+    /// ゲームの状態を遷移させる (ホストのみ)
+    pub fn change_status(
+        &mut self,
+        game_id: &u32,
+        user_id: UserId,
+        status: GameStatus,
+    ) -> Result<(), Error> {
+        self.hosted_game_mut(game_id, user_id)?.transition(status)
+    }
+
+    pub fn mark(
+        &mut self,
+        game_id: &u32,
+        user_id: UserId,
+        number: usize,
+    ) -> Result<MarkResult, Error> {
+        self.game_mut(game_id)?.mark(user_id, number)
+    }
+
+    /// 次の数字を抽選する (ホストのみ)
+    pub fn draw_next(&mut self, game_id: &u32, user_id: UserId) -> Result<Vec<DrawResult>, Error> {
+        self.hosted_game_mut(game_id, user_id)?.draw_next()
+    }
+
+    /// 指定した数字を抽選済みにする (ホストのみ)
+    pub fn draw(
+        &mut self,
+        game_id: &u32,
+        user_id: UserId,
+        number: usize,
+    ) -> Result<DrawResult, Error> {
+        self.hosted_game_mut(game_id, user_id)?.draw(number)
+    }
+
+    pub fn claim_bingo(&mut self, game_id: &u32, user_id: UserId) -> Result<Bingo, Error> {
+        self.game_mut(game_id)?.claim_bingo(user_id)
+    }
+
+    pub fn ranking(&self, game_id: &u32) -> Result<Vec<Rank>, Error> {
+        Ok(self.game(game_id)?.ranking())
+    }
+
+    fn game_mut(&mut self, game_id: &u32) -> Result<&mut Game, Error> {
+        self.games
+            .get_mut(game_id)
+            .ok_or(Error::NotFound { game_id: *game_id })
+    }
+
+    fn hosted_game_mut(&mut self, game_id: &u32, user_id: UserId) -> Result<&mut Game, Error> {
+        let game = self.game_mut(game_id)?;
+
+        if game.host != user_id {
+            return Err(Error::NotHost { user: user_id });
         }
+
+        Ok(game)
     }
 }
 
@@ -61,6 +219,152 @@ mod tests {
         assert_eq!(manager.games.get(&game.id).unwrap().id, game.id);
     }
 
+    #[test]
+    fn it_allocates_unique_ids_and_codes() {
+        let mut manager = GameManager {
+            rng: StdRng::seed_from_u64(0),
+            ..Default::default()
+        };
+        // 同じ乱数を持つので、衝突を避けなければ同じIDと参加コードになる
+        let mut other = manager.clone();
+
+        let a = manager
+            .create_game(UserId::new(1), GameMode::NORMAL, GameSettings::default())
+            .unwrap();
+        other.games.insert(a.id, a.clone());
+        other.codes.insert(a.code.clone(), a.id);
+        let b = other
+            .create_game(UserId::new(2), GameMode::NORMAL, GameSettings::default())
+            .unwrap();
+
+        assert_ne!(a.id, b.id);
+        assert_ne!(a.code, b.code);
+    }
+
+    #[test]
+    fn it_draws_seed_independently_of_id() {
+        let mut manager = GameManager {
+            rng: StdRng::seed_from_u64(0),
+            ..Default::default()
+        };
+
+        let a = manager
+            .create_game(UserId::new(1), GameMode::NORMAL, GameSettings::default())
+            .unwrap();
+        let b = manager
+            .create_game(UserId::new(2), GameMode::NORMAL, GameSettings::default())
+            .unwrap();
+
+        // シードはIDとは別に乱数から引く
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(a.id, rng.gen::<u32>());
+        assert_ne!(a.seed, StdRng::seed_from_u64(u64::from(a.id)).gen::<u64>());
+        assert_ne!(a.seed, u64::from(a.id));
+        assert_ne!(a.seed, b.seed);
+    }
+
+    #[test]
+    fn it_finds_game_by_code() {
+        let mut manager = GameManager::default();
+        let game = manager
+            .create_game(UserId::new(1), GameMode::NORMAL, GameSettings::default())
+            .unwrap();
+
+        let code = game.code().to_lowercase();
+        assert_eq!(manager.game_by_code(&code).unwrap().id, game.id);
+
+        let unknown = if game.code() == "ABC234" {
+            "ABC235"
+        } else {
+            "ABC234"
+        };
+        assert_eq!(
+            manager.game_by_code(unknown).unwrap_err(),
+            Error::UnknownCode {
+                code: unknown.to_string()
+            }
+        );
+        assert!(manager.game_by_code("not a code").is_err());
+    }
+
+    #[test]
+    fn it_rejects_invalid_settings() {
+        let mut manager = GameManager::default();
+        let settings = GameSettings {
+            board_size: 4,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            manager
+                .create_game(UserId::default(), GameMode::NORMAL, settings)
+                .unwrap_err(),
+            Error::Board(board::errors::Error::EvenSize { size: 4 })
+        );
+        assert!(manager.games.is_empty());
+    }
+
+    #[test]
+    fn it_requires_assets_in_asset_modes() {
+        let mut manager = GameManager::default();
+
+        assert_eq!(
+            manager
+                .create_game(UserId::default(), GameMode::FONT, GameSettings::default())
+                .unwrap_err(),
+            Error::NotEnoughAssets {
+                count: 75,
+                available: 0
+            }
+        );
+        assert!(manager.games.is_empty());
+    }
+
+    #[test]
+    fn it_uses_registered_numerals() {
+        struct Tally;
+
+        impl Numeral for Tally {
+            fn max(&self) -> usize {
+                100
+            }
+
+            fn format(&self, number: usize) -> Option<String> {
+                (1..=100).contains(&number).then(|| "|".repeat(number))
+            }
+
+            fn parse(&self, text: &str) -> Option<usize> {
+                text.chars().all(|c| c == '|').then_some(text.len())
+            }
+        }
+
+        let mut manager = GameManager::default();
+        let host = UserId::new(1);
+        let settings = GameSettings {
+            numeral: "TALLY".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            manager
+                .create_game(host, GameMode::FOREIGN, settings.clone())
+                .unwrap_err(),
+            Error::UnknownNumeral {
+                name: "TALLY".to_string()
+            }
+        );
+
+        manager.register_numeral("TALLY", Tally);
+        let game = manager
+            .create_game(host, GameMode::FOREIGN, settings)
+            .unwrap();
+        manager
+            .change_status(&game.id, host, GameStatus::RUNNING)
+            .unwrap();
+        let result = manager.draw_next(&game.id, host).unwrap().remove(0);
+        assert_eq!(result.label, Some("|".repeat(result.number)));
+    }
+
     #[test]
     fn it_cannot_create_game() {
         let mut manager = GameManager::default();
@@ -93,10 +397,24 @@ mod tests {
 
         let board = manager.join_game(&game.id, user).unwrap();
 
-        assert_eq!(user.get() + u64::from(game.id), board.id);
+        assert_eq!(manager.board(&game.id, user), Ok(&board));
         assert_eq!(board, manager.join_game(&game.id, user).unwrap());
     }
 
+    #[test]
+    fn it_can_list_games() {
+        let mut manager = GameManager::default();
+        assert!(manager.games().is_empty());
+
+        let game = manager
+            .create_game(UserId::default(), GameMode::NORMAL, GameSettings::default())
+            .unwrap();
+
+        let games = manager.games();
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].id, game.id);
+    }
+
     #[test]
     fn it_cannot_join_unknown_game() {
         let mut manager = GameManager::default();
@@ -105,4 +423,88 @@ mod tests {
 
         assert_eq!(Error::NotFound { game_id: 1 }, err);
     }
+
+    #[test]
+    fn it_can_draw_only_by_host() {
+        let mut manager = GameManager::default();
+        let (host, player) = (UserId::new(1), UserId::new(2));
+
+        let game = manager
+            .create_game(host, GameMode::NORMAL, GameSettings::default())
+            .unwrap();
+
+        manager
+            .change_status(&game.id, host, GameStatus::RUNNING)
+            .unwrap();
+
+        assert_eq!(
+            manager.draw_next(&game.id, player).unwrap_err(),
+            Error::NotHost { user: player }
+        );
+        assert!(manager.draw_next(&game.id, host).is_ok());
+    }
+
+    #[test]
+    fn it_can_change_status_only_by_host() {
+        let mut manager = GameManager::default();
+        let (host, player) = (UserId::new(1), UserId::new(2));
+
+        let game = manager
+            .create_game(host, GameMode::NORMAL, GameSettings::default())
+            .unwrap();
+
+        assert_eq!(
+            manager
+                .change_status(&game.id, player, GameStatus::RUNNING)
+                .unwrap_err(),
+            Error::NotHost { user: player }
+        );
+        assert!(manager
+            .change_status(&game.id, host, GameStatus::RUNNING)
+            .is_ok());
+        assert_eq!(
+            manager.game(&game.id).unwrap().status(),
+            GameStatus::RUNNING
+        );
+    }
+
+    #[test]
+    fn it_can_create_game_after_previous_one_is_over() {
+        let mut manager = GameManager::default();
+        let host = UserId::new(1);
+
+        for status in [GameStatus::CANCELLED, GameStatus::FINISHED] {
+            let game = manager
+                .create_game(host, GameMode::NORMAL, GameSettings::default())
+                .unwrap();
+            if status == GameStatus::FINISHED {
+                manager
+                    .change_status(&game.id, host, GameStatus::RUNNING)
+                    .unwrap();
+            }
+            manager.change_status(&game.id, host, status).unwrap();
+        }
+
+        assert!(manager
+            .create_game(host, GameMode::NORMAL, GameSettings::default())
+            .is_ok());
+    }
+
+    #[test]
+    fn it_cannot_mark_in_unknown_game() {
+        let mut manager = GameManager::default();
+
+        assert_eq!(
+            manager.mark(&1, UserId::default(), 1).unwrap_err(),
+            Error::NotFound { game_id: 1 }
+        );
+        assert_eq!(
+            manager.claim_bingo(&1, UserId::default()).unwrap_err(),
+            Error::NotFound { game_id: 1 }
+        );
+        assert_eq!(
+            manager.draw_next(&1, UserId::default()).unwrap_err(),
+            Error::NotFound { game_id: 1 }
+        );
+    }
 }
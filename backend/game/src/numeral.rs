@@ -0,0 +1,692 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// 数字の書き表し方
+///
+/// 1以上`max`以下の数字を書き表せ、書き表したものを数字に読み戻せる。
+pub trait Numeral: Send + Sync {
+    /// 書き表せる最大の数字
+    fn max(&self) -> usize;
+
+    /// numberを書き表す (0や`max`より大きい場合はNone)
+    fn format(&self, number: usize) -> Option<String>;
+
+    /// 書き表されたものを数字に読み戻す
+    fn parse(&self, text: &str) -> Option<usize>;
+}
+
+/// 名前で引ける数字の書き表し方の一覧
+#[derive(Clone, Default)]
+pub struct NumeralRegistry {
+    numerals: BTreeMap<String, Arc<dyn Numeral>>,
+}
+
+impl NumeralRegistry {
+    /// 空の一覧
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 組み込みの書き表し方を全て登録した一覧
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use game::numeral::NumeralRegistry;
+    /// let roman = NumeralRegistry::builtin().get("ROMAN").unwrap();
+    /// assert_eq!(roman.format(1994), Some("MCMXCIV".to_string()));
+    /// assert_eq!(roman.parse("MCMXCIV"), Some(1994));
+    /// ```
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        registry.register("KANJI", Kanji);
+        registry.register("ROMAN", Roman);
+        registry.register("THAI", Digits { zero: '๐' });
+        registry.register("ARABIC", Digits { zero: '٠' });
+        registry.register("ENGLISH", English);
+        registry.register("FRENCH", French);
+        registry.register("GERMAN", German);
+        registry
+    }
+
+    /// nameで書き表し方を登録する (同じ名前の場合は置き換える)
+    pub fn register(&mut self, name: &str, numeral: impl Numeral + 'static) {
+        self.numerals.insert(name.to_string(), Arc::new(numeral));
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn Numeral>> {
+        self.numerals.get(name).cloned()
+    }
+
+    /// 登録されている名前 (昇順)
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.numerals.keys().map(String::as_str)
+    }
+}
+
+impl fmt::Debug for NumeralRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+/// 漢数字 (一万二千三百四十五)
+pub struct Kanji;
+
+const KANJI_DIGITS: [char; 10] = ['〇', '一', '二', '三', '四', '五', '六', '七', '八', '九'];
+const KANJI_UNITS: [(char, usize); 3] = [('千', 1000), ('百', 100), ('十', 10)];
+
+impl Kanji {
+    /// 1万未満の数字 (十、百、千の前の一は書かない)
+    fn below_10000(number: usize) -> String {
+        let mut text = String::new();
+        for (unit, value) in KANJI_UNITS {
+            let digit = number / value % 10;
+            if digit > 1 {
+                text.push(KANJI_DIGITS[digit]);
+            }
+            if digit > 0 {
+                text.push(unit);
+            }
+        }
+        if !number.is_multiple_of(10) {
+            text.push(KANJI_DIGITS[number % 10]);
+        }
+        text
+    }
+}
+
+impl Numeral for Kanji {
+    fn max(&self) -> usize {
+        9999_9999
+    }
+
+    fn format(&self, number: usize) -> Option<String> {
+        if number == 0 || number > self.max() {
+            return None;
+        }
+
+        let (man, rest) = (number / 10000, number % 10000);
+        let mut text = String::new();
+        if man > 0 {
+            text.push_str(&Self::below_10000(man));
+            text.push('万');
+        }
+        text.push_str(&Self::below_10000(rest));
+
+        Some(text)
+    }
+
+    fn parse(&self, text: &str) -> Option<usize> {
+        let (mut total, mut section, mut current) = (0, 0, 0);
+        for c in text.chars() {
+            if let Some(digit) = KANJI_DIGITS.iter().position(|&d| d == c) {
+                current = digit;
+            } else if let Some(&(_, value)) = KANJI_UNITS.iter().find(|(unit, _)| *unit == c) {
+                section += current.max(1) * value;
+                current = 0;
+            } else if c == '万' {
+                total += (section + current) * 10000;
+                (section, current) = (0, 0);
+            } else {
+                return None;
+            }
+        }
+
+        Some(total + section + current).filter(|&number| number > 0)
+    }
+}
+
+/// ローマ数字 (MCMXCIV)
+pub struct Roman;
+
+const ROMAN_SYMBOLS: [(&str, usize); 13] = [
+    ("M", 1000),
+    ("CM", 900),
+    ("D", 500),
+    ("CD", 400),
+    ("C", 100),
+    ("XC", 90),
+    ("L", 50),
+    ("XL", 40),
+    ("X", 10),
+    ("IX", 9),
+    ("V", 5),
+    ("IV", 4),
+    ("I", 1),
+];
+
+impl Numeral for Roman {
+    fn max(&self) -> usize {
+        3999
+    }
+
+    fn format(&self, number: usize) -> Option<String> {
+        if number == 0 || number > self.max() {
+            return None;
+        }
+
+        let mut rest = number;
+        let mut text = String::new();
+        for (symbol, value) in ROMAN_SYMBOLS {
+            while rest >= value {
+                text.push_str(symbol);
+                rest -= value;
+            }
+        }
+
+        Some(text)
+    }
+
+    fn parse(&self, text: &str) -> Option<usize> {
+        let mut rest = text;
+        let mut number = 0;
+        for (symbol, value) in ROMAN_SYMBOLS {
+            while let Some(stripped) = rest.strip_prefix(symbol) {
+                number += value;
+                rest = stripped;
+            }
+        }
+
+        // IIIIやVVのような正しくない書き方は認めない
+        (rest.is_empty() && self.format(number).as_deref() == Some(text)).then_some(number)
+    }
+}
+
+/// 0から9の数字を別の文字に置き換えた数字 (タイ数字やアラビア・インド数字)
+pub struct Digits {
+    /// 0を表す文字 (1から9は続く文字)
+    pub zero: char,
+}
+
+impl Numeral for Digits {
+    fn max(&self) -> usize {
+        usize::MAX
+    }
+
+    fn format(&self, number: usize) -> Option<String> {
+        if number == 0 {
+            return None;
+        }
+
+        number
+            .to_string()
+            .chars()
+            .map(|c| char::from_u32(self.zero as u32 + c.to_digit(10)?))
+            .collect()
+    }
+
+    fn parse(&self, text: &str) -> Option<usize> {
+        let digits: String = text
+            .chars()
+            .map(|c| {
+                let digit = (c as u32).checked_sub(self.zero as u32)?;
+                char::from_digit(digit, 10)
+            })
+            .collect::<Option<String>>()?;
+
+        digits.parse().ok().filter(|&number| number > 0)
+    }
+}
+
+/// 英語 (twelve thousand three hundred forty-five)
+pub struct English;
+
+const ENGLISH_ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+const ENGLISH_TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+impl English {
+    fn below_100(number: usize) -> String {
+        match (number / 10, number % 10) {
+            (0 | 1, _) => ENGLISH_ONES[number].to_string(),
+            (tens, 0) => ENGLISH_TENS[tens].to_string(),
+            (tens, ones) => format!("{}-{}", ENGLISH_TENS[tens], ENGLISH_ONES[ones]),
+        }
+    }
+
+    fn below_1000(number: usize) -> String {
+        match (number / 100, number % 100) {
+            (0, rest) => Self::below_100(rest),
+            (hundreds, 0) => format!("{} hundred", ENGLISH_ONES[hundreds]),
+            (hundreds, rest) => {
+                format!(
+                    "{} hundred {}",
+                    ENGLISH_ONES[hundreds],
+                    Self::below_100(rest)
+                )
+            }
+        }
+    }
+}
+
+impl Numeral for English {
+    fn max(&self) -> usize {
+        999_999
+    }
+
+    fn format(&self, number: usize) -> Option<String> {
+        if number == 0 || number > self.max() {
+            return None;
+        }
+
+        Some(match (number / 1000, number % 1000) {
+            (0, rest) => Self::below_1000(rest),
+            (thousands, 0) => format!("{} thousand", Self::below_1000(thousands)),
+            (thousands, rest) => format!(
+                "{} thousand {}",
+                Self::below_1000(thousands),
+                Self::below_1000(rest)
+            ),
+        })
+    }
+
+    fn parse(&self, text: &str) -> Option<usize> {
+        let (mut total, mut current) = (0, 0);
+        for word in text.split([' ', '-']) {
+            match word {
+                "hundred" => current *= 100,
+                "thousand" => {
+                    total += current * 1000;
+                    current = 0;
+                }
+                _ => {
+                    current += ENGLISH_ONES.iter().position(|&w| w == word).or_else(|| {
+                        ENGLISH_TENS
+                            .iter()
+                            .rposition(|&w| w == word)
+                            .map(|t| t * 10)
+                    })?
+                }
+            }
+        }
+
+        Some(total + current).filter(|&number| number > 0)
+    }
+}
+
+/// フランス語 (quatre-vingt-dix-sept)
+pub struct French;
+
+const FRENCH_UNITS: [&str; 17] = [
+    "zéro", "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf", "dix", "onze",
+    "douze", "treize", "quatorze", "quinze", "seize",
+];
+const FRENCH_TENS: [&str; 7] = [
+    "",
+    "dix",
+    "vingt",
+    "trente",
+    "quarante",
+    "cinquante",
+    "soixante",
+];
+
+impl French {
+    fn below_100(number: usize) -> String {
+        match number {
+            0..=16 => FRENCH_UNITS[number].to_string(),
+            17..=19 => format!("dix-{}", FRENCH_UNITS[number - 10]),
+            20..=69 => match number % 10 {
+                0 => FRENCH_TENS[number / 10].to_string(),
+                1 => format!("{} et un", FRENCH_TENS[number / 10]),
+                ones => format!("{}-{}", FRENCH_TENS[number / 10], FRENCH_UNITS[ones]),
+            },
+            71 => "soixante et onze".to_string(),
+            70..=79 => format!("soixante-{}", Self::below_100(number - 60)),
+            80 => "quatre-vingts".to_string(),
+            _ => format!("quatre-vingt-{}", Self::below_100(number - 80)),
+        }
+    }
+
+    fn below_1000(number: usize) -> String {
+        match (number / 100, number % 100) {
+            (0, rest) => Self::below_100(rest),
+            (1, 0) => "cent".to_string(),
+            (1, rest) => format!("cent {}", Self::below_100(rest)),
+            (hundreds, 0) => format!("{} cents", FRENCH_UNITS[hundreds]),
+            (hundreds, rest) => {
+                format!("{} cent {}", FRENCH_UNITS[hundreds], Self::below_100(rest))
+            }
+        }
+    }
+}
+
+impl Numeral for French {
+    fn max(&self) -> usize {
+        999_999
+    }
+
+    fn format(&self, number: usize) -> Option<String> {
+        if number == 0 || number > self.max() {
+            return None;
+        }
+
+        let (thousands, rest) = (number / 1000, number % 1000);
+        let mut text = match thousands {
+            0 => String::new(),
+            1 => "mille".to_string(),
+            // milleの前ではvingtsとcentsのsが落ちる
+            _ => {
+                let prefix = Self::below_1000(thousands);
+                let prefix = match prefix.strip_suffix('s') {
+                    Some(stripped) if prefix.ends_with("vingts") || prefix.ends_with("cents") => {
+                        stripped.to_string()
+                    }
+                    _ => prefix,
+                };
+                format!("{prefix} mille")
+            }
+        };
+        if rest > 0 {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(&Self::below_1000(rest));
+        }
+
+        Some(text)
+    }
+
+    fn parse(&self, text: &str) -> Option<usize> {
+        let (mut total, mut current) = (0, 0);
+        let mut previous = "";
+        for word in text.split([' ', '-']) {
+            match word {
+                "et" => {}
+                "cent" | "cents" => current = current.max(1) * 100,
+                // quatre-vingtsは4と20の積
+                "vingt" | "vingts" if previous == "quatre" => current += 80 - 4,
+                "vingt" | "vingts" => current += 20,
+                "mille" => {
+                    total += current.max(1) * 1000;
+                    current = 0;
+                }
+                _ => {
+                    current += FRENCH_UNITS
+                        .iter()
+                        .position(|&w| w == word)
+                        .or_else(|| FRENCH_TENS.iter().rposition(|&w| w == word).map(|t| t * 10))?
+                }
+            }
+            previous = word;
+        }
+
+        Some(total + current).filter(|&number| number > 0)
+    }
+}
+
+/// ドイツ語 (siebenundneunzig)
+pub struct German;
+
+const GERMAN_UNITS: [&str; 20] = [
+    "null",
+    "eins",
+    "zwei",
+    "drei",
+    "vier",
+    "fünf",
+    "sechs",
+    "sieben",
+    "acht",
+    "neun",
+    "zehn",
+    "elf",
+    "zwölf",
+    "dreizehn",
+    "vierzehn",
+    "fünfzehn",
+    "sechzehn",
+    "siebzehn",
+    "achtzehn",
+    "neunzehn",
+];
+const GERMAN_TENS: [&str; 10] = [
+    "", "", "zwanzig", "dreißig", "vierzig", "fünfzig", "sechzig", "siebzig", "achtzig", "neunzig",
+];
+
+impl German {
+    /// 他の語の前に付く形 (einsはeinになる)
+    fn prefix(number: usize) -> &'static str {
+        match number {
+            1 => "ein",
+            _ => GERMAN_UNITS[number],
+        }
+    }
+
+    fn below_100(number: usize) -> String {
+        match (number / 10, number % 10) {
+            (0 | 1, _) => GERMAN_UNITS[number].to_string(),
+            (tens, 0) => GERMAN_TENS[tens].to_string(),
+            (tens, ones) => format!("{}und{}", Self::prefix(ones), GERMAN_TENS[tens]),
+        }
+    }
+
+    fn below_1000(number: usize) -> String {
+        match (number / 100, number % 100) {
+            (0, rest) => Self::below_100(rest),
+            (hundreds, 0) => format!("{}hundert", Self::prefix(hundreds)),
+            (hundreds, rest) => {
+                format!("{}hundert{}", Self::prefix(hundreds), Self::below_100(rest))
+            }
+        }
+    }
+}
+
+impl Numeral for German {
+    fn max(&self) -> usize {
+        999_999
+    }
+
+    fn format(&self, number: usize) -> Option<String> {
+        if number == 0 || number > self.max() {
+            return None;
+        }
+
+        let (thousands, rest) = (number / 1000, number % 1000);
+        let mut text = match thousands {
+            0 => String::new(),
+            _ => {
+                let prefix = Self::below_1000(thousands);
+                // tausendの前ではeinsはeinになる
+                let prefix = match prefix.strip_suffix("eins") {
+                    Some(stripped) => format!("{stripped}ein"),
+                    None => prefix,
+                };
+                format!("{prefix}tausend")
+            }
+        };
+        if rest > 0 {
+            text.push_str(&Self::below_1000(rest));
+        }
+
+        Some(text)
+    }
+
+    fn parse(&self, text: &str) -> Option<usize> {
+        let words: Vec<(&str, usize)> = GERMAN_UNITS
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(value, &word)| (word, value))
+            .chain(
+                GERMAN_TENS
+                    .iter()
+                    .enumerate()
+                    .skip(2)
+                    .map(|(tens, &word)| (word, tens * 10)),
+            )
+            .chain([("ein", 1)])
+            .collect();
+
+        let (mut total, mut current) = (0, 0);
+        let mut rest = text;
+        while !rest.is_empty() {
+            if let Some(stripped) = rest.strip_prefix("und") {
+                rest = stripped;
+            } else if let Some(stripped) = rest.strip_prefix("hundert") {
+                current = current.max(1) * 100;
+                rest = stripped;
+            } else if let Some(stripped) = rest.strip_prefix("tausend") {
+                total += current.max(1) * 1000;
+                current = 0;
+                rest = stripped;
+            } else {
+                // 最も長く一致する語を読む (sechzehnとsechsなど)
+                let (word, value) = words
+                    .iter()
+                    .filter(|(word, _)| rest.starts_with(word))
+                    .max_by_key(|(word, _)| word.len())?;
+                current += value;
+                rest = &rest[word.len()..];
+            }
+        }
+
+        Some(total + current).filter(|&number| number > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 1から`max`までの数字のうち確かめるもの (大きい数字は間引く)
+    fn samples(max: usize) -> impl Iterator<Item = usize> {
+        (1..=max.min(20_000)).chain((20_000..=max.min(9999_9999)).step_by(997))
+    }
+
+    #[test]
+    fn it_round_trips_every_builtin_numeral() {
+        let registry = NumeralRegistry::builtin();
+
+        for name in registry.names() {
+            let numeral = registry.get(name).unwrap();
+            for number in samples(numeral.max()) {
+                let text = numeral.format(number).unwrap();
+                assert_eq!(numeral.parse(&text), Some(number), "{name}: {text}");
+            }
+            assert_eq!(numeral.format(0), None, "{name}");
+        }
+    }
+
+    #[test]
+    fn it_writes_kanji() {
+        let cases = [
+            (10, "十"),
+            (11, "十一"),
+            (20, "二十"),
+            (105, "百五"),
+            (1000, "千"),
+            (2024, "二千二十四"),
+            (10000, "一万"),
+            (12345, "一万二千三百四十五"),
+            (1000_0000, "千万"),
+        ];
+        for (number, text) in cases {
+            assert_eq!(Kanji.format(number).as_deref(), Some(text));
+        }
+        assert_eq!(Kanji.format(1_0000_0000), None);
+    }
+
+    #[test]
+    fn it_writes_roman() {
+        for (number, text) in [
+            (4, "IV"),
+            (9, "IX"),
+            (14, "XIV"),
+            (40, "XL"),
+            (3999, "MMMCMXCIX"),
+        ] {
+            assert_eq!(Roman.format(number).as_deref(), Some(text));
+        }
+        assert_eq!(Roman.format(4000), None);
+        for text in ["IIII", "VV", "IXIX", "ABC", ""] {
+            assert_eq!(Roman.parse(text), None, "{text}");
+        }
+    }
+
+    #[test]
+    fn it_writes_digits() {
+        let registry = NumeralRegistry::builtin();
+
+        assert_eq!(
+            registry.get("THAI").unwrap().format(75).as_deref(),
+            Some("๗๕")
+        );
+        assert_eq!(
+            registry.get("ARABIC").unwrap().format(120).as_deref(),
+            Some("١٢٠")
+        );
+    }
+
+    #[test]
+    fn it_writes_words() {
+        let cases: [(&dyn Numeral, usize, &str); 13] = [
+            (&English, 15, "fifteen"),
+            (&English, 42, "forty-two"),
+            (&English, 100, "one hundred"),
+            (&English, 12345, "twelve thousand three hundred forty-five"),
+            (&French, 21, "vingt et un"),
+            (&French, 71, "soixante et onze"),
+            (&French, 80, "quatre-vingts"),
+            (&French, 97, "quatre-vingt-dix-sept"),
+            (&French, 200, "deux cents"),
+            (&French, 80_000, "quatre-vingt mille"),
+            (&German, 21, "einundzwanzig"),
+            (&German, 101, "einhunderteins"),
+            (&German, 1001, "eintausendeins"),
+        ];
+        for (numeral, number, text) in cases {
+            assert_eq!(numeral.format(number).as_deref(), Some(text));
+        }
+    }
+
+    #[test]
+    fn it_registers_custom_numeral() {
+        struct Tally;
+
+        impl Numeral for Tally {
+            fn max(&self) -> usize {
+                10
+            }
+
+            fn format(&self, number: usize) -> Option<String> {
+                (1..=10).contains(&number).then(|| "|".repeat(number))
+            }
+
+            fn parse(&self, text: &str) -> Option<usize> {
+                text.chars().all(|c| c == '|').then_some(text.len())
+            }
+        }
+
+        let mut registry = NumeralRegistry::new();
+        registry.register("TALLY", Tally);
+
+        assert_eq!(registry.names().collect::<Vec<_>>(), ["TALLY"]);
+        assert_eq!(registry.get("TALLY").unwrap().parse("|||"), Some(3));
+        assert!(registry.get("KANJI").is_none());
+    }
+}
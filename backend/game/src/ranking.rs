@@ -0,0 +1,129 @@
+use board::pattern::Pattern;
+use serde::{Deserialize, Serialize};
+use serenity::all::UserId;
+use std::collections::HashMap;
+
+/// 認められたビンゴ
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct Bingo {
+    pub user: UserId,
+    /// 何回目の抽選の時点で達成したか
+    pub draw_count: usize,
+    /// このビンゴで新しく揃ったライン
+    pub lines: Vec<Vec<usize>>,
+    /// 揃ったラインのパターン
+    pub patterns: Vec<Pattern>,
+}
+
+/// 順位表の1行
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Rank {
+    /// 順位 (同じ抽選回数でビンゴした場合は同順位)
+    pub rank: usize,
+    pub user: UserId,
+    /// 最初のビンゴを達成した抽選回数
+    pub draw_count: usize,
+    /// 認められたビンゴの回数
+    pub bingo_count: usize,
+}
+
+/// ビンゴの記録から順位表を作る
+/// 最初のビンゴを達成した抽選回数が少ない順に並び、同じ回数の場合は同順位になる
+///
+/// # 例
+///
+/// ```
+/// # use game::ranking::{ranking, Bingo};
+/// # use serenity::all::UserId;
+/// let bingo = |user, draw_count| Bingo { user: UserId::new(user), draw_count, lines: vec![], patterns: vec![] };
+/// let ranks = ranking(&[bingo(1, 10), bingo(2, 10), bingo(1, 12), bingo(3, 15)]);
+/// assert_eq!(
+///     ranks.iter().map(|rank| (rank.rank, rank.user.get())).collect::<Vec<_>>(),
+///     vec![(1, 1), (1, 2), (3, 3)]
+/// );
+/// ```
+pub fn ranking(bingos: &[Bingo]) -> Vec<Rank> {
+    let mut firsts: Vec<&Bingo> = vec![];
+    let mut counts: HashMap<UserId, usize> = HashMap::new();
+
+    for bingo in bingos {
+        let count = counts.entry(bingo.user).or_default();
+        if *count == 0 {
+            firsts.push(bingo);
+        }
+        *count += 1;
+    }
+
+    // 同じ抽選回数の場合はビンゴが認められた順
+    firsts.sort_by_key(|bingo| bingo.draw_count);
+
+    let mut ranks: Vec<Rank> = vec![];
+    for (i, bingo) in firsts.into_iter().enumerate() {
+        let rank = match ranks.last() {
+            Some(last) if last.draw_count == bingo.draw_count => last.rank,
+            _ => i + 1,
+        };
+
+        ranks.push(Rank {
+            rank,
+            user: bingo.user,
+            draw_count: bingo.draw_count,
+            bingo_count: counts[&bingo.user],
+        });
+    }
+
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bingo(user: u64, draw_count: usize) -> Bingo {
+        Bingo {
+            user: UserId::new(user),
+            draw_count,
+            lines: vec![],
+            patterns: vec![],
+        }
+    }
+
+    #[test]
+    fn it_ranks_by_first_bingo() {
+        assert_eq!(
+            ranking(&[bingo(2, 8), bingo(1, 12), bingo(2, 20)]),
+            vec![
+                Rank {
+                    rank: 1,
+                    user: UserId::new(2),
+                    draw_count: 8,
+                    bingo_count: 2,
+                },
+                Rank {
+                    rank: 2,
+                    user: UserId::new(1),
+                    draw_count: 12,
+                    bingo_count: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_shares_rank_on_same_draw() {
+        let ranks = ranking(&[bingo(3, 5), bingo(1, 9), bingo(2, 9), bingo(4, 11)]);
+
+        assert_eq!(
+            ranks
+                .iter()
+                .map(|rank| (rank.rank, rank.user.get()))
+                .collect::<Vec<_>>(),
+            vec![(1, 3), (2, 1), (2, 2), (4, 4)]
+        );
+    }
+
+    #[test]
+    fn it_returns_empty_ranking_without_bingo() {
+        assert!(ranking(&[]).is_empty());
+    }
+}
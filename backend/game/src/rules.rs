@@ -0,0 +1,957 @@
+use crate::asset::{AssetAssignment, AssetManifest};
+use crate::color::{ColorFormat, Palette};
+use crate::config::{GameMode, GameSettings};
+use crate::dice::DiceRoller;
+use crate::draw::{DrawDetail, Drawer};
+use crate::encoding::Encoding;
+use crate::errors::Error;
+use crate::formula::FormulaGenerator;
+use crate::mahjong::Wall;
+use crate::numeral::{Numeral, NumeralRegistry};
+use crate::slot::SlotMachine;
+use crate::symbol::Catalog;
+use board::board::Board;
+use board::cell::Cell;
+use board::generate::NumberRange;
+use board::tile::Tile;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+
+/// フリーマスの表記
+pub const FREE_LABEL: &str = "FREE";
+
+/// 抽選された数字とその詳細
+#[derive(Clone, PartialEq, Debug)]
+pub struct Drawn {
+    pub number: usize,
+    pub detail: Option<DrawDetail>,
+}
+
+/// ゲームモードごとの遊び方
+///
+/// 数字盤の作り方、抽選される数字、数字の見せ方、抽選と開け方の決まりをまとめる。
+/// 見せ方が変わっても判定は常に数字のまま行う。
+pub trait ModeRules: CloneRules + Send + Sync + fmt::Debug {
+    /// 数字盤に書かれうる数字の範囲
+    fn range(&self, settings: &GameSettings) -> NumberRange {
+        settings.range()
+    }
+
+    /// 数字盤を作れる設定かどうか検証する (パターンなどモードによらない設定は`GameSettings::validate`で検証する)
+    fn validate_settings(&self, settings: &GameSettings) -> Result<(), Error> {
+        Ok(self.range(settings).validate(settings.board_size)?)
+    }
+
+    /// 抽選される数字
+    fn pool(&self, settings: &GameSettings) -> Vec<usize> {
+        self.range(settings).usable(settings.board_size).collect()
+    }
+
+    /// idの数字盤を作る
+    fn board(&self, id: u64, settings: &GameSettings) -> Result<Board, Error> {
+        let mut board = Board::with_patterns(
+            id,
+            settings.board_size,
+            self.range(settings),
+            settings.patterns.clone(),
+        )?;
+        board.labels = self.labels(&board);
+
+        Ok(board)
+    }
+
+    /// 数字盤の各マスの表記 (数字のままの場合はNone)
+    fn labels(&self, _board: &Board) -> Option<Vec<Vec<String>>> {
+        None
+    }
+
+    /// 抽選された数字の表記 (数字のままの場合はNone)
+    fn call_label(&self, _number: usize) -> Option<String> {
+        None
+    }
+
+    /// 次の数字を抽選する
+    /// 1回で複数の数字を抽選するモードでは抽選した順に並ぶ
+    fn draw_next(&mut self, drawer: &mut Drawer) -> Result<Vec<Drawn>, Error> {
+        let number = drawer.draw_next()?;
+        let detail = self.detail(number);
+
+        Ok(vec![Drawn { number, detail }])
+    }
+
+    /// 抽選された数字の詳細 (数字を直接指定した場合も使われる)
+    fn detail(&mut self, _number: usize) -> Option<DrawDetail> {
+        None
+    }
+
+    /// 参加者がnumberを開けられるか検証する
+    /// 抽選されていない数字は開けられない
+    fn validate_mark(&self, drawer: &Drawer, number: usize) -> Result<(), Error> {
+        if !drawer.is_drawn(number) {
+            return Err(Error::NotDrawn { number });
+        }
+
+        Ok(())
+    }
+}
+
+/// `Box<dyn ModeRules>`をcloneするためのトレイト (`ModeRules`を実装すれば自動で実装される)
+pub trait CloneRules {
+    fn clone_box(&self) -> Box<dyn ModeRules>;
+}
+
+impl<T: ModeRules + Clone + 'static> CloneRules for T {
+    fn clone_box(&self) -> Box<dyn ModeRules> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn ModeRules> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// 遊び方を作るときに使えるもの
+pub struct ModeContext<'a> {
+    pub settings: &'a GameSettings,
+    /// ゲームごとに決まるシード
+    pub seed: u64,
+    pub assets: &'a AssetManifest,
+    /// 異国語数字モードで使える書き表し方
+    pub numerals: &'a NumeralRegistry,
+}
+
+/// ゲームモードから遊び方を作る関数
+pub type ModeFactory = fn(&ModeContext) -> Result<Box<dyn ModeRules>, Error>;
+
+/// ゲームモードごとの遊び方の一覧
+#[derive(Clone, Default)]
+pub struct ModeRegistry {
+    factories: HashMap<GameMode, ModeFactory>,
+}
+
+impl ModeRegistry {
+    /// 空の一覧
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 全てのゲームモードの遊び方を登録した一覧
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        registry.register(GameMode::NORMAL, |_| Ok(Box::new(Normal)));
+        registry.register(GameMode::SLOT, |context| {
+            Ok(Box::new(Slot::new(context.seed)))
+        });
+        registry.register(GameMode::DICE, |context| {
+            Ok(Box::new(Dice::new(context.settings, context.seed)?))
+        });
+        registry.register(GameMode::MAHJONG, |context| {
+            Ok(Box::new(Mahjong::new(context.seed)))
+        });
+        registry.register(GameMode::BINARY, |context| {
+            Ok(Box::new(Encoded::new(Encoding::BINARY, context.settings)))
+        });
+        registry.register(GameMode::HEXADECIMAL, |context| {
+            Ok(Box::new(Encoded::new(
+                Encoding::HEXADECIMAL,
+                context.settings,
+            )))
+        });
+        registry.register(GameMode::MIX, |context| {
+            Ok(Box::new(Mix::new(context.settings, context.seed)))
+        });
+        registry.register(GameMode::FORMULA, |context| {
+            Ok(Box::new(Formula::new(context.settings, context.seed)))
+        });
+        registry.register(GameMode::EMOJI, |_| Ok(Box::new(Symbol(Catalog::EMOJI))));
+        registry.register(GameMode::FLAG, |_| Ok(Box::new(Symbol(Catalog::FLAG))));
+        registry.register(GameMode::COLOR, |context| {
+            Ok(Box::new(Colors::new(context.settings, context.seed)?))
+        });
+        registry.register(GameMode::CELEBRITY, |context| {
+            Ok(Box::new(Assets::new(GameMode::CELEBRITY, context)?))
+        });
+        registry.register(GameMode::FONT, |context| {
+            Ok(Box::new(Assets::new(GameMode::FONT, context)?))
+        });
+        registry.register(GameMode::FOREIGN, |context| {
+            Ok(Box::new(Foreign::new(context.settings, context.numerals)?))
+        });
+        registry
+    }
+
+    /// modeの遊び方を登録する (同じモードの場合は置き換える)
+    pub fn register(&mut self, mode: GameMode, factory: ModeFactory) {
+        self.factories.insert(mode, factory);
+    }
+
+    /// modeの遊び方を作り、`context.settings`で数字盤を作れるか検証する
+    pub fn create(
+        &self,
+        mode: GameMode,
+        context: &ModeContext,
+    ) -> Result<Box<dyn ModeRules>, Error> {
+        let factory = self
+            .factories
+            .get(&mode)
+            .ok_or(Error::UnsupportedMode { mode })?;
+
+        let rules = factory(context)?;
+        rules.validate_settings(context.settings)?;
+        context.settings.validate()?;
+
+        Ok(rules)
+    }
+}
+
+/// 組み込みの遊び方の一覧
+pub fn registry() -> &'static ModeRegistry {
+    static REGISTRY: OnceLock<ModeRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(ModeRegistry::builtin)
+}
+
+/// 数字盤の各マスの表記 (フリーマスは`FREE_LABEL`)
+/// 表記できない数字が1つでもある場合はNone
+fn label_cells(
+    board: &Board,
+    mut label: impl FnMut(usize) -> Option<String>,
+) -> Option<Vec<Vec<String>>> {
+    board
+        .cells()
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|&cell| match cell {
+                    Cell::FREE => Some(FREE_LABEL.to_string()),
+                    _ => label(cell.number()),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// 数字のまま遊ぶ
+#[derive(Clone, Debug)]
+pub struct Normal;
+
+impl ModeRules for Normal {}
+
+/// パチスロ
+/// スロットの停止位置から抽選する数字を決め、7が揃った場合は2つ抽選する
+#[derive(Clone, Debug)]
+pub struct Slot {
+    slot: SlotMachine,
+}
+
+impl Slot {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            slot: SlotMachine::new(seed),
+        }
+    }
+}
+
+impl ModeRules for Slot {
+    fn draw_next(&mut self, drawer: &mut Drawer) -> Result<Vec<Drawn>, Error> {
+        let spin = self.slot.spin();
+        let mut drawn = vec![];
+        for i in 0..spin.outcome.draws() {
+            // ボーナスの抽選は残りの数字がなければ行わない
+            if i > 0 && drawer.remaining() == 0 {
+                break;
+            }
+
+            let number = drawer.draw_index(spin.value() + i)?;
+            let detail = (i == 0).then(|| DrawDetail::SLOT(spin.clone()));
+            drawn.push(Drawn { number, detail });
+        }
+
+        Ok(drawn)
+    }
+}
+
+/// サイコロ
+/// 抽選済みでない数字が出るまでサイコロを振った場合と同じ確率で出目を決め、詳細に含める
+#[derive(Clone, Debug)]
+pub struct Dice {
+    dice: DiceRoller,
+}
+
+impl Dice {
+    /// サイコロの組が正しくない場合はエラーになる
+    pub fn new(settings: &GameSettings, seed: u64) -> Result<Self, Error> {
+        let dice = settings.dice_set();
+        dice.validate()?;
+
+        Ok(Self {
+            dice: DiceRoller::new(dice, seed),
+        })
+    }
+}
+
+impl ModeRules for Dice {
+    fn validate_settings(&self, settings: &GameSettings) -> Result<(), Error> {
+        Ok(self.dice.dice().validate_for(settings.board_size)?)
+    }
+
+    fn range(&self, _settings: &GameSettings) -> NumberRange {
+        self.dice.dice().range()
+    }
+
+    /// サイコロで出る可能性のある数字
+    fn pool(&self, _settings: &GameSettings) -> Vec<usize> {
+        self.dice.dice().distribution().into_keys().collect()
+    }
+
+    fn board(&self, id: u64, settings: &GameSettings) -> Result<Board, Error> {
+        Ok(Board::with_dice(
+            id,
+            settings.board_size,
+            self.dice.dice().clone(),
+            settings.patterns.clone(),
+        )?)
+    }
+
+    fn draw_next(&mut self, drawer: &mut Drawer) -> Result<Vec<Drawn>, Error> {
+        let roll = self
+            .dice
+            .roll_among(drawer.undrawn())
+            .ok_or(Error::NoNumbersLeft)?;
+        drawer.draw(roll.number)?;
+
+        Ok(vec![Drawn {
+            number: roll.number,
+            detail: Some(DrawDetail::DICE(roll)),
+        }])
+    }
+}
+
+/// 麻雀
+/// 抽選済みでない種類の牌が出るまで山から牌を引き、その牌の番号を抽選する
+#[derive(Clone, Debug)]
+pub struct Mahjong {
+    wall: Wall,
+}
+
+impl Mahjong {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            wall: Wall::new(seed),
+        }
+    }
+}
+
+impl ModeRules for Mahjong {
+    fn validate_settings(&self, settings: &GameSettings) -> Result<(), Error> {
+        Ok(Tile::validate_board(settings.board_size)?)
+    }
+
+    fn range(&self, _settings: &GameSettings) -> NumberRange {
+        NumberRange {
+            min: 1,
+            max: Tile::KINDS,
+        }
+    }
+
+    /// 牌の番号
+    fn pool(&self, _settings: &GameSettings) -> Vec<usize> {
+        Tile::all().map(Tile::code).collect()
+    }
+
+    fn board(&self, id: u64, settings: &GameSettings) -> Result<Board, Error> {
+        Ok(Board::with_tiles(
+            id,
+            settings.board_size,
+            settings.patterns.clone(),
+        )?)
+    }
+
+    fn draw_next(&mut self, drawer: &mut Drawer) -> Result<Vec<Drawn>, Error> {
+        if drawer.remaining() == 0 {
+            return Err(Error::NoNumbersLeft);
+        }
+
+        let tile = loop {
+            let tile = self.wall.draw().ok_or(Error::NoNumbersLeft)?;
+            if drawer.draw(tile.code()).is_ok() {
+                break tile;
+            }
+        };
+
+        Ok(vec![Drawn {
+            number: tile.code(),
+            detail: Some(DrawDetail::MAHJONG {
+                tile,
+                wall: self.wall.remaining(),
+            }),
+        }])
+    }
+}
+
+/// 2進数か16進数
+/// 桁数は数字盤に書かれうる最大の数字に揃える
+#[derive(Clone, Debug)]
+pub struct Encoded {
+    encoding: Encoding,
+    max: usize,
+}
+
+impl Encoded {
+    pub fn new(encoding: Encoding, settings: &GameSettings) -> Self {
+        Self {
+            encoding,
+            max: settings.range().max,
+        }
+    }
+}
+
+impl ModeRules for Encoded {
+    fn labels(&self, board: &Board) -> Option<Vec<Vec<String>>> {
+        label_cells(board, |number| self.call_label(number))
+    }
+
+    fn call_label(&self, number: usize) -> Option<String> {
+        Some(self.encoding.encode(number, self.max))
+    }
+}
+
+/// 2進数と16進数
+/// マスごとの表記は数字盤のID、抽選ごとの表記はゲームのシードと数字から決める
+#[derive(Clone, Debug)]
+pub struct Mix {
+    max: usize,
+    seed: u64,
+}
+
+impl Mix {
+    pub fn new(settings: &GameSettings, seed: u64) -> Self {
+        Self {
+            max: settings.range().max,
+            seed,
+        }
+    }
+
+    fn encode(&self, number: usize, rng: &mut StdRng) -> String {
+        let encoding = match rng.gen_bool(0.5) {
+            true => Encoding::BINARY,
+            false => Encoding::HEXADECIMAL,
+        };
+        encoding.encode(number, self.max)
+    }
+}
+
+impl ModeRules for Mix {
+    fn labels(&self, board: &Board) -> Option<Vec<Vec<String>>> {
+        let mut rng = StdRng::seed_from_u64(board.id);
+        label_cells(board, |number| Some(self.encode(number, &mut rng)))
+    }
+
+    fn call_label(&self, number: usize) -> Option<String> {
+        let mut rng = StdRng::seed_from_u64(self.seed ^ number as u64);
+        Some(self.encode(number, &mut rng))
+    }
+}
+
+/// 数式
+/// 数字を直接指定した場合も数式を作る
+#[derive(Clone, Debug)]
+pub struct Formula {
+    formula: FormulaGenerator,
+}
+
+impl Formula {
+    pub fn new(settings: &GameSettings, seed: u64) -> Self {
+        Self {
+            formula: FormulaGenerator::new(settings.difficulty, seed),
+        }
+    }
+}
+
+impl ModeRules for Formula {
+    fn detail(&mut self, number: usize) -> Option<DrawDetail> {
+        let expression = self.formula.generate(number).to_string();
+        Some(DrawDetail::FORMULA { expression })
+    }
+}
+
+/// 絵文字か国旗
+/// 数字盤に書かれうる数字は記号の数で決まり、`GameSettings::number_range`は使われない
+#[derive(Clone, Debug)]
+pub struct Symbol(pub Catalog);
+
+impl ModeRules for Symbol {
+    fn range(&self, _settings: &GameSettings) -> NumberRange {
+        self.0.range()
+    }
+
+    fn labels(&self, board: &Board) -> Option<Vec<Vec<String>>> {
+        label_cells(board, |number| self.0.symbol(number))
+    }
+
+    fn call_label(&self, number: usize) -> Option<String> {
+        self.0.symbol(number)
+    }
+}
+
+/// カラーコード
+/// マスは`#RRGGBB`、抽選は`GameSettings::color_format`の表記になる
+#[derive(Clone, Debug)]
+pub struct Colors {
+    palette: Palette,
+    format: ColorFormat,
+}
+
+impl Colors {
+    /// seedからパレットを作る (色の数が`color::MAX_COLORS`を超える場合はエラー)
+    pub fn new(settings: &GameSettings, seed: u64) -> Result<Self, Error> {
+        Ok(Self {
+            palette: Palette::new(seed, settings.range())?,
+            format: settings.color_format,
+        })
+    }
+}
+
+impl ModeRules for Colors {
+    fn labels(&self, board: &Board) -> Option<Vec<Vec<String>>> {
+        label_cells(board, |number| {
+            let color = self.palette.color(number)?;
+            Some(ColorFormat::HEX.format(color))
+        })
+    }
+
+    fn call_label(&self, number: usize) -> Option<String> {
+        let color = self.palette.color(number)?;
+        Some(self.format.format(color))
+    }
+}
+
+/// 著名人かフォント
+/// マスと抽選は割り当てられた素材のIDになり、抽選の詳細に素材が付く
+#[derive(Clone, Debug)]
+pub struct Assets {
+    assignment: AssetAssignment,
+}
+
+impl Assets {
+    /// ゲームのシードからmodeの素材を割り当てる
+    pub fn new(mode: GameMode, context: &ModeContext) -> Result<Self, Error> {
+        Ok(Self {
+            assignment: context
+                .assets
+                .assign(&mode, context.settings.range(), context.seed)?,
+        })
+    }
+}
+
+impl ModeRules for Assets {
+    fn labels(&self, board: &Board) -> Option<Vec<Vec<String>>> {
+        label_cells(board, |number| self.call_label(number))
+    }
+
+    fn call_label(&self, number: usize) -> Option<String> {
+        Some(self.assignment.asset(number)?.id.clone())
+    }
+
+    fn detail(&mut self, number: usize) -> Option<DrawDetail> {
+        self.assignment
+            .asset(number)
+            .cloned()
+            .map(DrawDetail::ASSET)
+    }
+}
+
+/// 異国語数字
+/// 抽選された数字だけを`GameSettings::numeral`の書き表し方にし、マスは数字のまま
+#[derive(Clone)]
+pub struct Foreign {
+    name: String,
+    numeral: Arc<dyn Numeral>,
+}
+
+impl Foreign {
+    /// `settings.numeral`の書き表し方をnumeralsから探す (見つからない場合はエラー)
+    pub fn new(settings: &GameSettings, numerals: &NumeralRegistry) -> Result<Self, Error> {
+        let name = settings.numeral.clone();
+        let numeral = numerals
+            .get(&name)
+            .ok_or_else(|| Error::UnknownNumeral { name: name.clone() })?;
+
+        Ok(Self { name, numeral })
+    }
+}
+
+impl fmt::Debug for Foreign {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Foreign").field("name", &self.name).finish()
+    }
+}
+
+impl ModeRules for Foreign {
+    /// 数字の範囲の全ての数字を書き表せる必要がある
+    fn validate_settings(&self, settings: &GameSettings) -> Result<(), Error> {
+        let range = self.range(settings);
+        range.validate(settings.board_size)?;
+        if range.max > self.numeral.max() {
+            return Err(Error::NumeralOutOfRange {
+                name: self.name.clone(),
+                max: self.numeral.max(),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn call_label(&self, number: usize) -> Option<String> {
+        self.numeral.format(number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::MAX_COLORS;
+
+    fn create(mode: GameMode, settings: &GameSettings) -> Box<dyn ModeRules> {
+        let context = ModeContext {
+            settings,
+            seed: 0,
+            assets: &AssetManifest::default(),
+            numerals: &NumeralRegistry::builtin(),
+        };
+        registry().create(mode, &context).unwrap()
+    }
+
+    fn validate(mode: GameMode, settings: &GameSettings) -> Result<(), Error> {
+        let context = ModeContext {
+            settings,
+            seed: 0,
+            assets: &AssetManifest::default(),
+            numerals: &NumeralRegistry::builtin(),
+        };
+        registry().create(mode, &context).map(|_| ())
+    }
+
+    #[test]
+    fn it_registers_every_mode() {
+        let settings = GameSettings::default();
+        let modes = [
+            GameMode::NORMAL,
+            GameMode::SLOT,
+            GameMode::DICE,
+            GameMode::MAHJONG,
+            GameMode::BINARY,
+            GameMode::HEXADECIMAL,
+            GameMode::MIX,
+            GameMode::FORMULA,
+            GameMode::EMOJI,
+            GameMode::COLOR,
+            GameMode::FLAG,
+            GameMode::FOREIGN,
+        ];
+
+        for mode in modes {
+            let rules = create(mode, &settings);
+            let board = rules.board(1, &settings).unwrap();
+            let pool = rules.pool(&settings);
+
+            // 数字盤の数字は全て抽選されうる
+            for &number in board.numbers().iter().flatten().filter(|&&n| n != 0) {
+                assert!(pool.contains(&number), "{mode:?}: {number}");
+            }
+        }
+
+        // 素材がない場合は素材を使うモードを作れない
+        let context = ModeContext {
+            settings: &settings,
+            seed: 0,
+            assets: &AssetManifest::default(),
+            numerals: &NumeralRegistry::builtin(),
+        };
+        assert!(registry().create(GameMode::FONT, &context).is_err());
+    }
+
+    #[test]
+    fn it_validates_numeral_in_foreign_mode() {
+        let settings = GameSettings {
+            numeral: "ROMAN".to_string(),
+            ..Default::default()
+        };
+        let numerals = NumeralRegistry::builtin();
+
+        let rules = Foreign::new(&settings, &numerals).unwrap();
+        assert_eq!(rules.call_label(14).as_deref(), Some("XIV"));
+        assert_eq!(
+            Foreign::new(&settings, &NumeralRegistry::new()).unwrap_err(),
+            Error::UnknownNumeral {
+                name: "ROMAN".to_string()
+            }
+        );
+
+        let settings = GameSettings {
+            number_range: Some(NumberRange { min: 1, max: 5000 }),
+            ..settings
+        };
+        assert_eq!(
+            rules.validate_settings(&settings),
+            Err(Error::NumeralOutOfRange {
+                name: "ROMAN".to_string(),
+                max: 3999
+            })
+        );
+        assert_eq!(validate(GameMode::NORMAL, &settings), Ok(()));
+    }
+
+    #[test]
+    fn it_rejects_invalid_board_size() {
+        for size in [0, 4] {
+            let settings = GameSettings {
+                board_size: size,
+                ..Default::default()
+            };
+            assert!(matches!(
+                validate(GameMode::NORMAL, &settings),
+                Err(Error::Board(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn it_validates_number_range() {
+        let settings = GameSettings {
+            number_range: Some(NumberRange { min: 1, max: 24 }),
+            ..Default::default()
+        };
+        assert_eq!(
+            validate(GameMode::NORMAL, &settings),
+            Err(Error::Board(board::errors::Error::SizeTooLarge {
+                size: 5,
+                min: 1,
+                max: 24
+            }))
+        );
+
+        let settings = GameSettings {
+            board_size: 3,
+            number_range: Some(NumberRange { min: 10, max: 18 }),
+            ..Default::default()
+        };
+        assert_eq!(validate(GameMode::NORMAL, &settings), Ok(()));
+    }
+
+    #[test]
+    fn it_validates_common_settings() {
+        let settings = GameSettings {
+            patterns: vec![],
+            ..Default::default()
+        };
+        assert_eq!(
+            validate(GameMode::NORMAL, &settings),
+            Err(Error::Board(board::errors::Error::NoPatterns))
+        );
+    }
+
+    #[test]
+    fn it_validates_dice_in_dice_mode() {
+        let settings: GameSettings =
+            serde_json::from_str(r#"{"dice":{"dice":[6,6],"mode":"SUM"}}"#).unwrap();
+
+        // 範囲は使われないので他のモードでは問題ない
+        assert_eq!(validate(GameMode::NORMAL, &settings), Ok(()));
+        assert_eq!(
+            validate(GameMode::DICE, &settings),
+            Err(Error::Board(board::errors::Error::NotEnoughNumbers {
+                size: 5,
+                count: 11
+            }))
+        );
+        assert_eq!(validate(GameMode::DICE, &GameSettings::default()), Ok(()));
+
+        let settings: GameSettings =
+            serde_json::from_str(r#"{"dice":{"dice":[1000000],"mode":"SUM"}}"#).unwrap();
+        assert_eq!(
+            validate(GameMode::DICE, &settings),
+            Err(Error::Board(board::errors::Error::InvalidDice))
+        );
+    }
+
+    #[test]
+    fn it_limits_board_size_in_mahjong_mode() {
+        let settings = GameSettings {
+            board_size: 7,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            validate(GameMode::MAHJONG, &settings),
+            Err(Error::Board(board::errors::Error::NotEnoughNumbers {
+                size: 7,
+                count: 34
+            }))
+        );
+        assert_eq!(
+            validate(GameMode::MAHJONG, &GameSettings::default()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn it_uses_catalog_range_in_symbol_modes() {
+        let settings = GameSettings {
+            number_range: Some(NumberRange { min: 1, max: 24 }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            create(GameMode::EMOJI, &settings).range(&settings),
+            NumberRange { min: 1, max: 120 }
+        );
+        assert_eq!(validate(GameMode::EMOJI, &settings), Ok(()));
+        assert!(validate(GameMode::NORMAL, &settings).is_err());
+    }
+
+    #[test]
+    fn it_limits_colors_in_color_mode() {
+        let settings = GameSettings {
+            board_size: 11,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            validate(GameMode::COLOR, &settings),
+            Err(Error::TooManyColors {
+                count: 165,
+                max: MAX_COLORS
+            })
+        );
+        assert_eq!(validate(GameMode::NORMAL, &settings), Ok(()));
+    }
+
+    #[test]
+    fn it_reports_unregistered_mode() {
+        let settings = GameSettings::default();
+        let context = ModeContext {
+            settings: &settings,
+            seed: 0,
+            assets: &AssetManifest::default(),
+            numerals: &NumeralRegistry::builtin(),
+        };
+
+        assert_eq!(
+            ModeRegistry::new()
+                .create(GameMode::NORMAL, &context)
+                .unwrap_err(),
+            Error::UnsupportedMode {
+                mode: GameMode::NORMAL
+            }
+        );
+    }
+
+    #[test]
+    fn it_labels_every_cell_in_binary() {
+        let settings = GameSettings {
+            board_size: 3,
+            ..Default::default()
+        };
+        let rules = create(GameMode::BINARY, &settings);
+        let board = rules.board(1, &settings).unwrap();
+        let labels = board.labels.as_ref().unwrap();
+
+        assert_eq!(labels[1][1], FREE_LABEL);
+        for (row, labels) in board.numbers().iter().zip(labels) {
+            for (&number, label) in row.iter().zip(labels).filter(|(&n, _)| n != 0) {
+                assert_eq!(
+                    usize::from_str_radix(label.trim_start_matches("0b"), 2),
+                    Ok(number)
+                );
+                assert_eq!(label.len(), "0b".len() + 6);
+            }
+        }
+    }
+
+    #[test]
+    fn it_keeps_plain_numbers_in_normal_mode() {
+        let settings = GameSettings::default();
+        let rules = create(GameMode::NORMAL, &settings);
+
+        assert_eq!(rules.board(1, &settings).unwrap().labels, None);
+        assert_eq!(rules.call_label(15), None);
+    }
+
+    #[test]
+    fn it_mixes_encodings_deterministically() {
+        let settings = GameSettings::default();
+        let rules = create(GameMode::MIX, &settings);
+        let labels = rules.board(1, &settings).unwrap().labels.unwrap();
+
+        assert_eq!(
+            rules.board(1, &settings).unwrap().labels,
+            Some(labels.clone())
+        );
+        let cells: Vec<&String> = labels.iter().flatten().collect();
+        assert!(cells.iter().any(|label| label.starts_with("0b")));
+        assert!(cells.iter().any(|label| label.starts_with("0x")));
+
+        assert_eq!(rules.call_label(15), rules.call_label(15));
+    }
+
+    #[test]
+    fn it_labels_cells_with_symbols() {
+        let settings = GameSettings::default();
+        let rules = create(GameMode::FLAG, &settings);
+        let board = rules.board(1, &settings).unwrap();
+        let labels = board.labels.as_ref().unwrap();
+
+        assert_eq!(labels[2][2], FREE_LABEL);
+        assert_eq!(
+            labels[0][0],
+            Catalog::FLAG.symbol(board.numbers()[0][0]).unwrap()
+        );
+        assert_eq!(rules.call_label(114), Some("🇯🇵".to_string()));
+    }
+
+    #[test]
+    fn it_labels_cells_with_colors() {
+        let settings = GameSettings {
+            color_format: ColorFormat::RGB,
+            ..Default::default()
+        };
+        let rules = create(GameMode::COLOR, &settings);
+        let labels = rules.board(1, &settings).unwrap().labels.unwrap();
+
+        assert_eq!(labels[2][2], FREE_LABEL);
+        assert!(labels[0][0].starts_with('#'));
+        assert_eq!(labels[0][0].len(), 7);
+        assert!(rules.call_label(15).unwrap().starts_with("rgb("));
+    }
+
+    #[test]
+    fn it_writes_calls_in_foreign_numerals() {
+        let settings = GameSettings {
+            numeral: "GERMAN".to_string(),
+            ..Default::default()
+        };
+        let rules = create(GameMode::FOREIGN, &settings);
+
+        assert_eq!(rules.board(1, &settings).unwrap().labels, None);
+        assert_eq!(rules.call_label(75), Some("fünfundsiebzig".to_string()));
+    }
+
+    #[test]
+    fn it_requires_drawn_number_to_mark() {
+        let rules = create(GameMode::NORMAL, &GameSettings::default());
+        let mut drawer = Drawer::new(0, 1..=75);
+        let number = drawer.draw_next().unwrap();
+
+        assert_eq!(rules.validate_mark(&drawer, number), Ok(()));
+        let undrawn = (1..=75).find(|&n| n != number).unwrap();
+        assert_eq!(
+            rules.validate_mark(&drawer, undrawn),
+            Err(Error::NotDrawn { number: undrawn })
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// リールの図柄
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Symbol {
+    CHERRY,
+    BELL,
+    WATERMELON,
+    REPLAY,
+    BAR,
+    SEVEN,
+}
+
+use Symbol::*;
+
+/// 各リールの図柄の並び (21コマ)
+pub const REEL_STRIPS: [[Symbol; 21]; 3] = [
+    [
+        SEVEN, REPLAY, BELL, CHERRY, WATERMELON, SEVEN, BELL, BAR, REPLAY, BELL, SEVEN, REPLAY,
+        BELL, WATERMELON, BAR, SEVEN, BELL, CHERRY, REPLAY, BELL, WATERMELON,
+    ],
+    [
+        REPLAY, SEVEN, CHERRY, BELL, REPLAY, WATERMELON, SEVEN, REPLAY, BAR, CHERRY, BELL, SEVEN,
+        WATERMELON, BELL, REPLAY, CHERRY, SEVEN, BELL, REPLAY, WATERMELON, BAR,
+    ],
+    [
+        BELL, REPLAY, WATERMELON, SEVEN, BELL, REPLAY, CHERRY, BELL, SEVEN, REPLAY, WATERMELON,
+        BELL, REPLAY, SEVEN, BELL, REPLAY, BAR, WATERMELON, SEVEN, REPLAY, CHERRY,
+    ],
+];
+
+/// 図柄の組み合わせによる役
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Outcome {
+    /// 数字を1つ抽選する
+    SINGLE,
+    /// 7が揃った: 数字を2つ抽選する
+    DOUBLE,
+}
+
+impl Outcome {
+    /// 図柄の組み合わせから役を決める
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use game::slot::{Outcome, Symbol};
+    /// assert_eq!(Outcome::of([Symbol::SEVEN; 3]), Outcome::DOUBLE);
+    /// assert_eq!(Outcome::of([Symbol::SEVEN, Symbol::SEVEN, Symbol::BAR]), Outcome::SINGLE);
+    /// ```
+    pub fn of(symbols: [Symbol; 3]) -> Self {
+        match symbols {
+            [SEVEN, SEVEN, SEVEN] => Outcome::DOUBLE,
+            _ => Outcome::SINGLE,
+        }
+    }
+
+    /// この役で抽選する数字の数
+    pub fn draws(self) -> usize {
+        match self {
+            Outcome::SINGLE => 1,
+            Outcome::DOUBLE => 2,
+        }
+    }
+}
+
+/// スロットを回した結果
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Spin {
+    /// 各リールの停止位置 (中段に止まったコマ)
+    pub stops: [usize; 3],
+    /// 中段に止まった図柄
+    pub symbols: [Symbol; 3],
+    pub outcome: Outcome,
+}
+
+impl Spin {
+    fn new(stops: [usize; 3]) -> Self {
+        let symbols = [0, 1, 2].map(|reel| REEL_STRIPS[reel][stops[reel]]);
+
+        Self {
+            stops,
+            symbols,
+            outcome: Outcome::of(symbols),
+        }
+    }
+
+    /// 停止位置の組み合わせを1つの値にしたもの (0以上21^3未満)
+    /// 残っている数字のどれを抽選するかはこの値で決まる
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use game::slot::SlotMachine;
+    /// let spin = SlotMachine::new(0).spin();
+    /// assert!(spin.value() < 21 * 21 * 21);
+    /// ```
+    pub fn value(&self) -> usize {
+        self.stops
+            .iter()
+            .enumerate()
+            .map(|(reel, &stop)| stop * REEL_STRIPS[reel].len().pow(reel as u32))
+            .sum()
+    }
+}
+
+/// 3リールのスロットマシン
+///
+/// 停止位置はシードから決まるため、同じシードであれば同じ順番で止まる
+#[derive(Clone, Debug)]
+pub struct SlotMachine {
+    rng: StdRng,
+}
+
+impl SlotMachine {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// リールを回して止める
+    pub fn spin(&mut self) -> Spin {
+        let stops = [0, 1, 2].map(|reel| self.rng.gen_range(0..REEL_STRIPS[reel].len()));
+
+        Spin::new(stops)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_is_reproducible_with_same_seed() {
+        let (mut a, mut b) = (SlotMachine::new(7), SlotMachine::new(7));
+
+        for _ in 0..100 {
+            assert_eq!(a.spin(), b.spin());
+        }
+    }
+
+    #[test]
+    fn it_shows_symbols_at_stop_positions() {
+        let mut slot = SlotMachine::new(0);
+
+        for _ in 0..100 {
+            let spin = slot.spin();
+            for reel in 0..3 {
+                assert_eq!(spin.symbols[reel], REEL_STRIPS[reel][spin.stops[reel]]);
+            }
+            assert_eq!(spin.outcome, Outcome::of(spin.symbols));
+        }
+    }
+
+    #[test]
+    fn it_gives_double_only_on_three_sevens() {
+        // 各リールの7の位置
+        let spin = Spin::new([0, 1, 3]);
+        assert_eq!(spin.symbols, [SEVEN; 3]);
+        assert_eq!(spin.outcome, Outcome::DOUBLE);
+        assert_eq!(spin.outcome.draws(), 2);
+
+        assert_eq!(Spin::new([0, 1, 4]).outcome, Outcome::SINGLE);
+        assert_eq!(Spin::new([15, 16, 18]).outcome, Outcome::DOUBLE);
+    }
+
+    #[test]
+    fn it_maps_stops_to_distinct_values() {
+        assert_eq!(Spin::new([0, 0, 0]).value(), 0);
+        assert_eq!(Spin::new([1, 0, 0]).value(), 1);
+        assert_eq!(Spin::new([0, 1, 0]).value(), 21);
+        assert_eq!(Spin::new([20, 20, 20]).value(), 21 * 21 * 21 - 1);
+    }
+}
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+/// ゲームの状態
+///
+/// ```text
+/// LOBBY --start--> RUNNING --pause--> PAUSED
+///                  RUNNING <--resume-- PAUSED
+/// RUNNING, PAUSED --finish--> FINISHED
+/// LOBBY, RUNNING, PAUSED --cancel--> CANCELLED
+/// ```
+#[derive(Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameStatus {
+    /// 参加者を募集中
+    #[default]
+    LOBBY,
+    /// 抽選中
+    RUNNING,
+    /// 一時停止中
+    PAUSED,
+    /// 終了した
+    FINISHED,
+    /// 中止された
+    CANCELLED,
+}
+
+impl GameStatus {
+    /// toの状態に遷移できるかどうか
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use game::status::GameStatus;
+    /// assert!(GameStatus::LOBBY.can_transition_to(GameStatus::RUNNING));
+    /// assert!(!GameStatus::LOBBY.can_transition_to(GameStatus::FINISHED));
+    /// ```
+    pub fn can_transition_to(self, to: GameStatus) -> bool {
+        matches!(
+            (self, to),
+            (GameStatus::LOBBY, GameStatus::RUNNING)
+                | (GameStatus::RUNNING, GameStatus::PAUSED)
+                | (GameStatus::PAUSED, GameStatus::RUNNING)
+                | (
+                    GameStatus::RUNNING | GameStatus::PAUSED,
+                    GameStatus::FINISHED
+                )
+                | (
+                    GameStatus::LOBBY | GameStatus::RUNNING | GameStatus::PAUSED,
+                    GameStatus::CANCELLED
+                )
+        )
+    }
+
+    /// 終了または中止されているかどうか
+    pub fn is_over(self) -> bool {
+        matches!(self, GameStatus::FINISHED | GameStatus::CANCELLED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [GameStatus; 5] = [
+        GameStatus::LOBBY,
+        GameStatus::RUNNING,
+        GameStatus::PAUSED,
+        GameStatus::FINISHED,
+        GameStatus::CANCELLED,
+    ];
+
+    #[test]
+    fn it_cannot_leave_finished_or_cancelled() {
+        for from in [GameStatus::FINISHED, GameStatus::CANCELLED] {
+            for to in ALL {
+                assert!(!from.can_transition_to(to));
+            }
+        }
+    }
+
+    #[test]
+    fn it_cannot_transition_to_same_status() {
+        for status in ALL {
+            assert!(!status.can_transition_to(status));
+        }
+    }
+
+    #[test]
+    fn it_cannot_return_to_lobby() {
+        for from in ALL {
+            assert!(!from.can_transition_to(GameStatus::LOBBY));
+        }
+    }
+
+    #[test]
+    fn it_can_pause_and_resume() {
+        assert!(GameStatus::RUNNING.can_transition_to(GameStatus::PAUSED));
+        assert!(GameStatus::PAUSED.can_transition_to(GameStatus::RUNNING));
+        assert!(!GameStatus::LOBBY.can_transition_to(GameStatus::PAUSED));
+    }
+}
@@ -0,0 +1,126 @@
+use board::generate::NumberRange;
+use serde::{Deserialize, Serialize};
+
+/// 同梱の絵文字
+const EMOJIS: [&str; 120] = [
+    "😀", "😂", "😍", "😎", "🤔", "😴", "😭", "😡", "🥳", "🤯", "😱", "🤖", "👻", "💀", "👽", "🎃",
+    "💩", "🙈", "🙉", "🙊", "🐶", "🐱", "🐭", "🐹", "🐰", "🦊", "🐻", "🐼", "🐨", "🐯", "🦁", "🐮",
+    "🐷", "🐸", "🐵", "🐔", "🐧", "🐦", "🦆", "🦉", "🐴", "🦄", "🐝", "🐛", "🦋", "🐌", "🐞", "🐢",
+    "🐍", "🐙", "🦑", "🦀", "🐠", "🐬", "🐳", "🦈", "🐊", "🦒", "🐘", "🦔", "🍎", "🍊", "🍋", "🍌",
+    "🍉", "🍇", "🍓", "🍒", "🍑", "🍍", "🥝", "🥑", "🍆", "🥕", "🌽", "🍄", "🍞", "🧀", "🍖", "🍔",
+    "🍟", "🍕", "🌭", "🌮", "🍣", "🍙", "🍜", "🍩", "🍪", "🎂", "⚽", "🏀", "🏈", "⚾", "🎾", "🏐",
+    "🎱", "🏓", "🎯", "🎲", "🎸", "🎺", "🎻", "🥁", "🎤", "🚗", "🚕", "🚌", "🚑", "🚒", "🚲", "🚀",
+    "🛸", "🚁", "⛵", "🌈", "⭐", "🌙", "🔥", "❄",
+];
+
+/// ISO 3166-1の国と地域のコード (alpha-2)
+const COUNTRIES: [&str; 249] = [
+    "AD", "AE", "AF", "AG", "AI", "AL", "AM", "AO", "AQ", "AR", "AS", "AT", "AU", "AW", "AX", "AZ",
+    "BA", "BB", "BD", "BE", "BF", "BG", "BH", "BI", "BJ", "BL", "BM", "BN", "BO", "BQ", "BR", "BS",
+    "BT", "BV", "BW", "BY", "BZ", "CA", "CC", "CD", "CF", "CG", "CH", "CI", "CK", "CL", "CM", "CN",
+    "CO", "CR", "CU", "CV", "CW", "CX", "CY", "CZ", "DE", "DJ", "DK", "DM", "DO", "DZ", "EC", "EE",
+    "EG", "EH", "ER", "ES", "ET", "FI", "FJ", "FK", "FM", "FO", "FR", "GA", "GB", "GD", "GE", "GF",
+    "GG", "GH", "GI", "GL", "GM", "GN", "GP", "GQ", "GR", "GS", "GT", "GU", "GW", "GY", "HK", "HM",
+    "HN", "HR", "HT", "HU", "ID", "IE", "IL", "IM", "IN", "IO", "IQ", "IR", "IS", "IT", "JE", "JM",
+    "JO", "JP", "KE", "KG", "KH", "KI", "KM", "KN", "KP", "KR", "KW", "KY", "KZ", "LA", "LB", "LC",
+    "LI", "LK", "LR", "LS", "LT", "LU", "LV", "LY", "MA", "MC", "MD", "ME", "MF", "MG", "MH", "MK",
+    "ML", "MM", "MN", "MO", "MP", "MQ", "MR", "MS", "MT", "MU", "MV", "MW", "MX", "MY", "MZ", "NA",
+    "NC", "NE", "NF", "NG", "NI", "NL", "NO", "NP", "NR", "NU", "NZ", "OM", "PA", "PE", "PF", "PG",
+    "PH", "PK", "PL", "PM", "PN", "PR", "PS", "PT", "PW", "PY", "QA", "RE", "RO", "RS", "RU", "RW",
+    "SA", "SB", "SC", "SD", "SE", "SG", "SH", "SI", "SJ", "SK", "SL", "SM", "SN", "SO", "SR", "SS",
+    "ST", "SV", "SX", "SY", "SZ", "TC", "TD", "TF", "TG", "TH", "TJ", "TK", "TL", "TM", "TN", "TO",
+    "TR", "TT", "TV", "TW", "TZ", "UA", "UG", "UM", "US", "UY", "UZ", "VA", "VC", "VE", "VG", "VI",
+    "VN", "VU", "WF", "WS", "YE", "YT", "ZA", "ZM", "ZW",
+];
+
+/// 数字の代わりに使う記号の一覧
+///
+/// 数字nは一覧のn-1番目の記号に対応し、数字盤や抽選では数字のまま扱う。
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Catalog {
+    /// 絵文字
+    EMOJI,
+    /// 国旗 (地域指示記号2文字の組)
+    FLAG,
+}
+
+impl Catalog {
+    /// 記号の数
+    pub fn len(self) -> usize {
+        match self {
+            Catalog::EMOJI => EMOJIS.len(),
+            Catalog::FLAG => COUNTRIES.len(),
+        }
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.len() == 0
+    }
+
+    /// 記号に対応する数字の範囲 (1から記号の数まで)
+    pub fn range(self) -> NumberRange {
+        NumberRange {
+            min: 1,
+            max: self.len(),
+        }
+    }
+
+    /// 数字に対応する記号
+    ///
+    /// # 例
+    ///
+    /// ```
+    /// # use game::symbol::Catalog;
+    /// assert_eq!(Catalog::EMOJI.symbol(1), Some("😀".to_string()));
+    /// assert_eq!(Catalog::FLAG.symbol(114), Some("🇯🇵".to_string()));
+    /// assert_eq!(Catalog::FLAG.symbol(0), None);
+    /// ```
+    pub fn symbol(self, number: usize) -> Option<String> {
+        let index = number.checked_sub(1)?;
+
+        match self {
+            Catalog::EMOJI => EMOJIS.get(index).map(|emoji| emoji.to_string()),
+            Catalog::FLAG => COUNTRIES.get(index).map(|code| flag(code)),
+        }
+    }
+}
+
+/// 国コードを地域指示記号の組 (国旗) にする
+fn flag(code: &str) -> String {
+    code.chars()
+        .filter_map(|c| char::from_u32('🇦' as u32 + (c as u32 - 'A' as u32)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn it_has_distinct_symbols() {
+        for catalog in [Catalog::EMOJI, Catalog::FLAG] {
+            let symbols: HashSet<String> = (1..=catalog.len())
+                .map(|number| catalog.symbol(number).unwrap())
+                .collect();
+
+            assert_eq!(symbols.len(), catalog.len());
+            assert_eq!(catalog.symbol(catalog.len() + 1), None);
+        }
+    }
+
+    #[test]
+    fn it_makes_flags_from_regional_indicators() {
+        assert_eq!(flag("US"), "🇺🇸");
+        assert!(COUNTRIES
+            .iter()
+            .all(|code| code.len() == 2 && code.chars().all(|c| c.is_ascii_uppercase())));
+    }
+
+    #[test]
+    fn it_fits_large_boards() {
+        // 1列あたりの個数が列数以上あれば数字盤を作れる
+        assert!(Catalog::EMOJI.range().validate(9).is_ok());
+        assert!(Catalog::FLAG.range().validate(15).is_ok());
+    }
+}
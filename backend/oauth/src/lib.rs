@@ -190,7 +190,7 @@ struct AccessTokenResponse {
     // scope: String,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Clone, Debug)]
 pub struct User {
     pub id: UserId,
     pub name: String,
@@ -1,5 +1,6 @@
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
+use game::errors::Error;
 
 pub(crate) struct AppError {
     pub(crate) status: StatusCode,
@@ -17,4 +18,81 @@ impl IntoResponse for AppError {
     }
 }
 
+/// ゲームのエラーをHTTPのステータスに対応させる
+impl From<Error> for AppError {
+    fn from(error: Error) -> Self {
+        let status = match error {
+            Error::NotFound { .. } | Error::UnknownCode { .. } => StatusCode::NOT_FOUND,
+
+            Error::NotParticipant { .. } | Error::NotHost { .. } => StatusCode::FORBIDDEN,
+
+            Error::MaxPlayers
+            | Error::OngoingGame { .. }
+            | Error::InvalidTransition { .. }
+            | Error::InvalidStatus { .. }
+            | Error::AlreadyDrawn { .. }
+            | Error::AlreadyClaimed { .. }
+            | Error::NoNumbersLeft => StatusCode::CONFLICT,
+
+            Error::Board(_)
+            | Error::TooManyColors { .. }
+            | Error::UnknownNumeral { .. }
+            | Error::NumeralOutOfRange { .. }
+            | Error::NotEnoughAssets { .. }
+            | Error::UnsupportedMode { .. }
+            | Error::InvalidNumber { .. }
+            | Error::NotDrawn { .. }
+            | Error::NoBingo => StatusCode::BAD_REQUEST,
+
+            Error::PaletteExhausted { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        Self {
+            status,
+            message: Some(error.to_string()),
+        }
+    }
+}
+
 pub(crate) type ResponseResult<T> = std::result::Result<T, AppError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serenity::all::UserId;
+
+    #[test]
+    fn it_maps_game_errors_to_status() {
+        let cases = [
+            (Error::NotFound { game_id: 1 }, StatusCode::NOT_FOUND),
+            (
+                Error::NotParticipant {
+                    user: UserId::new(1),
+                },
+                StatusCode::FORBIDDEN,
+            ),
+            (Error::MaxPlayers, StatusCode::CONFLICT),
+            (
+                Error::AlreadyClaimed {
+                    user: UserId::new(1),
+                },
+                StatusCode::CONFLICT,
+            ),
+            (
+                Error::Board(board::errors::Error::EvenSize { size: 4 }),
+                StatusCode::BAD_REQUEST,
+            ),
+            (
+                Error::PaletteExhausted { count: 150 },
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+        ];
+
+        for (error, status) in cases {
+            let message = error.to_string();
+            let error = AppError::from(error);
+            assert_eq!(error.status, status);
+            assert_eq!(error.message, Some(message));
+        }
+    }
+}
@@ -1,9 +1,11 @@
 mod error;
+mod manager;
 mod routes;
+mod session;
 
-use axum::http::StatusCode;
-use axum::Router;
-use game::manager::GameManager;
+use crate::manager::SharedGameManager;
+use crate::session::SessionStore;
+use game::asset::AssetManifest;
 use oauth::security::SecurityManager;
 use oauth::DiscordOAuth;
 use std::env;
@@ -24,6 +26,12 @@ async fn main() {
         )),
     };
 
+    // 著名人モードとフォントモードで使う素材 (指定しない場合はどちらのモードも遊べない)
+    let assets = match env::var("ASSET_MANIFEST") {
+        Ok(path) => AssetManifest::load(path).expect("Failed to load asset manifest"),
+        Err(_) => AssetManifest::default(),
+    };
+
     let state = AppState {
         oauth: *DiscordOAuth::new(
             env::var("DISCORD_CLIENT_ID").expect("DISCORD_CLIENT_ID not set"),
@@ -34,29 +42,50 @@ async fn main() {
             ),
             oauth_security,
         ),
-        manager: GameManager::new(),
+        manager: SharedGameManager::with_assets(assets),
+        sessions: SessionStore::default(),
     };
 
     log::info!("Server starting");
 
     let listener = TcpListener::bind("0.0.0.0:8080").await.unwrap();
-    let router = Router::new()
-        .merge(routes::login::route())
-        .nest("/game", routes::game::route());
-
-    axum::serve(
-        listener,
-        Router::new()
-            .nest("/api", router)
-            .fallback(|| async { StatusCode::NOT_FOUND })
-            .with_state(state),
-    )
-    .await
-    .unwrap();
+
+    axum::serve(listener, routes::app(state)).await.unwrap();
 }
 
 #[derive(Clone)]
 struct AppState {
     oauth: DiscordOAuth,
-    manager: GameManager,
+    manager: SharedGameManager,
+    sessions: SessionStore,
+}
+
+#[cfg(test)]
+impl AppState {
+    /// テスト用の`AppState`
+    pub(crate) fn for_test() -> Self {
+        Self {
+            oauth: *DiscordOAuth::new(
+                "client_id".to_string(),
+                "client_secret".to_string(),
+                "http://localhost/login".to_string(),
+                Arc::new(Mutex::new(
+                    oauth::security::memory::InMemorySecurityManager::default(),
+                )),
+            ),
+            manager: SharedGameManager::default(),
+            sessions: SessionStore::default(),
+        }
+    }
+
+    /// idのユーザーとしてログインし、セッショントークンを返す
+    pub(crate) async fn login(&self, id: u64) -> String {
+        self.sessions
+            .create(oauth::User {
+                id: serenity::all::UserId::new(id),
+                name: format!("user{id}"),
+                avatar: String::new(),
+            })
+            .await
+    }
 }
@@ -0,0 +1,403 @@
+use board::analysis::ReachKind;
+use board::board::{Board, OpenResult};
+use game::asset::AssetManifest;
+use game::config::{GameMode, GameSettings};
+use game::draw::DrawResult;
+use game::errors::Error;
+use game::event::ServerEvent;
+use game::game::{Game, GameInfo, MarkResult};
+use game::manager::GameManager;
+use game::ranking::{Bingo, Rank};
+use game::status::GameStatus;
+use serenity::all::UserId;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{broadcast, RwLock};
+
+/// 1ゲームあたりに溜めておけるイベント数
+const EVENT_CAPACITY: usize = 64;
+
+/// 全リクエストで共有される`GameManager`へのハンドル
+///
+/// axumはリクエスト毎に`AppState`をcloneするため、
+/// `GameManager`を値で持つと作成したゲームが次のリクエストから見えなくなる。
+/// cloneしても同じ`GameManager`を指すように`Arc<RwLock<_>>`で包んでいる。
+#[derive(Clone, Default)]
+pub(crate) struct SharedGameManager {
+    inner: Arc<RwLock<GameManager>>,
+    events: Arc<RwLock<HashMap<u32, broadcast::Sender<ServerEvent>>>>,
+}
+
+impl SharedGameManager {
+    /// assetsの素材を使う`SharedGameManager`
+    pub(crate) fn with_assets(assets: AssetManifest) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(GameManager::with_assets(assets))),
+            ..Self::default()
+        }
+    }
+
+    pub(crate) async fn assets(&self) -> Arc<AssetManifest> {
+        Arc::clone(self.inner.read().await.assets())
+    }
+
+    pub(crate) async fn create_game(
+        &self,
+        host: UserId,
+        mode: GameMode,
+        settings: GameSettings,
+    ) -> Result<Game, Error> {
+        let game = self.inner.write().await.create_game(host, mode, settings)?;
+
+        self.events
+            .write()
+            .await
+            .insert(game.id(), broadcast::channel(EVENT_CAPACITY).0);
+
+        Ok(game)
+    }
+
+    pub(crate) async fn join_game(&self, game_id: u32, user_id: UserId) -> Result<Board, Error> {
+        let (joined, board) = {
+            let mut manager = self.inner.write().await;
+            let joined = !manager.game(&game_id)?.has_participant(&user_id);
+            (joined, manager.join_game(&game_id, user_id)?)
+        };
+
+        if joined {
+            self.publish(game_id, ServerEvent::PlayerJoined { user: user_id })
+                .await;
+        }
+
+        Ok(board)
+    }
+
+    /// 全ゲームの情報 (参加者の数字盤は含まない)
+    pub(crate) async fn games(&self) -> Vec<GameInfo> {
+        self.inner
+            .read()
+            .await
+            .games()
+            .into_iter()
+            .map(Game::info)
+            .collect()
+    }
+
+    /// ゲームの状態を遷移させて参加者全員に通知する
+    /// 終了した場合は順位表も通知する
+    pub(crate) async fn change_status(
+        &self,
+        game_id: u32,
+        user_id: UserId,
+        status: GameStatus,
+    ) -> Result<(), Error> {
+        let ranking = {
+            let mut manager = self.inner.write().await;
+            manager.change_status(&game_id, user_id, status)?;
+            manager.ranking(&game_id)?
+        };
+
+        self.publish(game_id, ServerEvent::StatusChanged { status })
+            .await;
+        if status == GameStatus::FINISHED {
+            self.publish(game_id, ServerEvent::GameFinished { ranking })
+                .await;
+        }
+
+        Ok(())
+    }
+
+    /// 数字を開け、リーチやビンゴになった場合は参加者全員に通知する
+    pub(crate) async fn mark(
+        &self,
+        game_id: u32,
+        user_id: UserId,
+        number: usize,
+    ) -> Result<MarkResult, Error> {
+        let result = self.inner.write().await.mark(&game_id, user_id, number)?;
+
+        self.publish_reach(game_id, user_id, &result.opened).await;
+        if let Some(bingo) = &result.bingo {
+            self.publish_bingo(game_id, bingo).await;
+        }
+
+        Ok(result)
+    }
+
+    /// 数字を抽選し、抽選された数字と各参加者のリーチやビンゴを通知する
+    /// numberを指定した場合はその数字を抽選済みにする
+    /// 1回で複数の数字が抽選された場合は抽選順に通知する
+    pub(crate) async fn draw(
+        &self,
+        game_id: u32,
+        user_id: UserId,
+        number: Option<usize>,
+    ) -> Result<Vec<DrawResult>, Error> {
+        let results = {
+            let mut manager = self.inner.write().await;
+            match number {
+                Some(number) => vec![manager.draw(&game_id, user_id, number)?],
+                None => manager.draw_next(&game_id, user_id)?,
+            }
+        };
+
+        for result in &results {
+            self.publish(
+                game_id,
+                ServerEvent::NumberDrawn {
+                    number: result.number,
+                    detail: result.detail.clone(),
+                    label: result.label.clone(),
+                },
+            )
+            .await;
+            for (&user, opened) in &result.opened {
+                self.publish_reach(game_id, user, opened).await;
+            }
+            for bingo in &result.bingos {
+                self.publish_bingo(game_id, bingo).await;
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// ビンゴの申告を検証して記録し、正しければ参加者全員に通知する
+    pub(crate) async fn claim_bingo(&self, game_id: u32, user_id: UserId) -> Result<Bingo, Error> {
+        let bingo = self.inner.write().await.claim_bingo(&game_id, user_id)?;
+
+        self.publish_bingo(game_id, &bingo).await;
+
+        Ok(bingo)
+    }
+
+    /// ユーザーがゲームのホストか参加者であることを確かめる
+    pub(crate) async fn ensure_member(&self, game_id: u32, user_id: UserId) -> Result<(), Error> {
+        let manager = self.inner.read().await;
+        let game = manager.game(&game_id)?;
+
+        if game.host() == user_id || game.has_participant(&user_id) {
+            Ok(())
+        } else {
+            Err(Error::NotParticipant { user: user_id })
+        }
+    }
+
+    /// ゲームのイベントを購読する
+    pub(crate) async fn subscribe(
+        &self,
+        game_id: u32,
+    ) -> Result<broadcast::Receiver<ServerEvent>, Error> {
+        self.events
+            .read()
+            .await
+            .get(&game_id)
+            .map(broadcast::Sender::subscribe)
+            .ok_or(Error::NotFound { game_id })
+    }
+
+    pub(crate) async fn info(&self, game_id: u32) -> Result<GameInfo, Error> {
+        Ok(self.inner.read().await.game(&game_id)?.info())
+    }
+
+    /// 参加者の数字盤 (開けたマスを含む)
+    pub(crate) async fn board(&self, game_id: u32, user_id: UserId) -> Result<Board, Error> {
+        self.inner.read().await.board(&game_id, user_id).cloned()
+    }
+
+    /// 参加コードからゲームを探す (参加者の数字盤は含まない)
+    pub(crate) async fn game_by_code(&self, code: &str) -> Result<GameInfo, Error> {
+        Ok(self.inner.read().await.game_by_code(code)?.info())
+    }
+
+    pub(crate) async fn ranking(&self, game_id: u32) -> Result<Vec<Rank>, Error> {
+        self.inner.read().await.ranking(&game_id)
+    }
+
+    /// 新しくリーチになったラインがあれば通知する
+    async fn publish_reach(&self, game_id: u32, user: UserId, opened: &OpenResult) {
+        if !opened.new_reach.is_empty() {
+            self.publish(
+                game_id,
+                ServerEvent::Reach {
+                    user,
+                    lines: opened.new_reach.clone(),
+                    kind: ReachKind::from_count(opened.reach_count),
+                },
+            )
+            .await
+        }
+    }
+
+    async fn publish_bingo(&self, game_id: u32, bingo: &Bingo) {
+        self.publish(
+            game_id,
+            ServerEvent::Bingo {
+                user: bingo.user,
+                lines: bingo.lines.clone(),
+                patterns: bingo.patterns.clone(),
+            },
+        )
+        .await
+    }
+
+    async fn publish(&self, game_id: u32, event: ServerEvent) {
+        if let Some(sender) = self.events.read().await.get(&game_id) {
+            // 購読者がいない場合は失敗するが、誰も見ていないだけなので無視してよい
+            let _ = sender.send(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game::draw::DrawDetail;
+
+    #[tokio::test]
+    async fn it_shares_games_between_clones() {
+        let manager = SharedGameManager::default();
+        let cloned = manager.clone();
+
+        let game = manager
+            .create_game(UserId::new(1), GameMode::NORMAL, GameSettings::default())
+            .await
+            .unwrap();
+
+        let games = cloned.games().await;
+        assert_eq!(games.len(), 1);
+        assert_eq!(game.id(), games[0].id);
+        assert!(cloned.join_game(game.id(), UserId::new(2)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn it_shares_assets_without_copying() {
+        let manager = SharedGameManager::with_assets(AssetManifest::default());
+
+        assert!(Arc::ptr_eq(
+            &manager.assets().await,
+            &manager.clone().assets().await
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_publishes_player_joined_once() {
+        let manager = SharedGameManager::default();
+        let game = manager
+            .create_game(UserId::new(1), GameMode::NORMAL, GameSettings::default())
+            .await
+            .unwrap();
+        let mut events = manager.subscribe(game.id()).await.unwrap();
+
+        manager.join_game(game.id(), UserId::new(2)).await.unwrap();
+        manager.join_game(game.id(), UserId::new(2)).await.unwrap();
+
+        assert_eq!(
+            events.recv().await.unwrap(),
+            ServerEvent::PlayerJoined {
+                user: UserId::new(2)
+            }
+        );
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn it_publishes_drawn_number() {
+        let manager = SharedGameManager::default();
+        let host = UserId::new(1);
+        let game = manager
+            .create_game(host, GameMode::NORMAL, GameSettings::default())
+            .await
+            .unwrap();
+        manager
+            .change_status(game.id(), host, GameStatus::RUNNING)
+            .await
+            .unwrap();
+        let mut events = manager.subscribe(game.id()).await.unwrap();
+
+        let result = manager.draw(game.id(), host, None).await.unwrap().remove(0);
+
+        assert_eq!(
+            events.recv().await.unwrap(),
+            ServerEvent::NumberDrawn {
+                number: result.number,
+                detail: None,
+                label: None,
+            }
+        );
+        assert_eq!(
+            manager.draw(game.id(), host, Some(result.number)).await,
+            Err(Error::AlreadyDrawn {
+                number: result.number
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn it_publishes_slot_result() {
+        let manager = SharedGameManager::default();
+        let host = UserId::new(1);
+        let game = manager
+            .create_game(host, GameMode::SLOT, GameSettings::default())
+            .await
+            .unwrap();
+        manager
+            .change_status(game.id(), host, GameStatus::RUNNING)
+            .await
+            .unwrap();
+        let mut events = manager.subscribe(game.id()).await.unwrap();
+
+        let results = manager.draw(game.id(), host, None).await.unwrap();
+
+        let ServerEvent::NumberDrawn { number, detail, .. } = events.recv().await.unwrap() else {
+            panic!("number should be drawn first");
+        };
+        assert_eq!(number, results[0].number);
+        assert!(matches!(detail, Some(DrawDetail::SLOT(_))));
+    }
+
+    #[tokio::test]
+    async fn it_publishes_ranking_when_finished() {
+        let manager = SharedGameManager::default();
+        let host = UserId::new(1);
+        let game = manager
+            .create_game(host, GameMode::NORMAL, GameSettings::default())
+            .await
+            .unwrap();
+        let mut events = manager.subscribe(game.id()).await.unwrap();
+
+        for status in [GameStatus::RUNNING, GameStatus::FINISHED] {
+            manager
+                .change_status(game.id(), host, status)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            events.recv().await.unwrap(),
+            ServerEvent::StatusChanged {
+                status: GameStatus::RUNNING
+            }
+        );
+        assert_eq!(
+            events.recv().await.unwrap(),
+            ServerEvent::StatusChanged {
+                status: GameStatus::FINISHED
+            }
+        );
+        assert_eq!(
+            events.recv().await.unwrap(),
+            ServerEvent::GameFinished { ranking: vec![] }
+        );
+    }
+
+    #[tokio::test]
+    async fn it_cannot_subscribe_unknown_game() {
+        let manager = SharedGameManager::default();
+
+        assert_eq!(
+            manager.subscribe(1).await.unwrap_err(),
+            Error::NotFound { game_id: 1 }
+        );
+    }
+}
@@ -0,0 +1,12 @@
+use crate::error::ResponseResult;
+use crate::AppState;
+use axum::extract::State;
+use game::asset::AssetManifest;
+use std::sync::Arc;
+
+/// 著名人モードとフォントモードで使う素材 (数字盤や抽選のIDから素材を引くため)
+pub(crate) async fn get_assets(
+    State(state): State<AppState>,
+) -> ResponseResult<axum::response::Json<Arc<AssetManifest>>> {
+    Ok(axum::response::Json(state.manager.assets().await))
+}
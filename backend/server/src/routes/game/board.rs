@@ -0,0 +1,28 @@
+use crate::error::ResponseResult;
+use crate::session::AuthUser;
+use crate::AppState;
+use axum::extract::{Path, State};
+use board::analysis::Analysis;
+use board::board::Board;
+
+/// ログイン中のユーザーの数字盤 (開けたマスを含む)
+pub(crate) async fn get_board(
+    State(state): State<AppState>,
+    Path(game_id): Path<u32>,
+    auth: AuthUser,
+) -> ResponseResult<axum::response::Json<Board>> {
+    Ok(axum::response::Json(
+        state.manager.board(game_id, auth.user.id).await?,
+    ))
+}
+
+/// ログイン中のユーザーの数字盤の分析結果 (各ラインの残りマス数とビンゴになる数字)
+pub(crate) async fn get_analysis(
+    State(state): State<AppState>,
+    Path(game_id): Path<u32>,
+    auth: AuthUser,
+) -> ResponseResult<axum::response::Json<Analysis>> {
+    let board = state.manager.board(game_id, auth.user.id).await?;
+
+    Ok(axum::response::Json(board.analyze()))
+}
@@ -0,0 +1,14 @@
+use crate::error::ResponseResult;
+use crate::AppState;
+use axum::extract::{Path, State};
+use game::game::GameInfo;
+
+/// 参加コードからゲームを探す
+pub(crate) async fn find_game(
+    State(state): State<AppState>,
+    Path(code): Path<String>,
+) -> ResponseResult<axum::response::Json<GameInfo>> {
+    Ok(axum::response::Json(
+        state.manager.game_by_code(&code).await?,
+    ))
+}
@@ -1,43 +1,28 @@
-use crate::error::{AppError, ResponseResult};
+use crate::error::ResponseResult;
+use crate::session::AuthUser;
 use crate::AppState;
 use axum::extract::State;
-use axum::http::StatusCode;
 use game::config::{GameMode, GameSettings};
-use game::errors::Error;
-use game::game::Game;
+use game::game::GameInfo;
 use serde::Deserialize;
-use serenity::all::UserId;
 
+/// ゲームを作り、参加者に見せる情報を返す
 pub(crate) async fn new_game(
-    State(mut state): State<AppState>,
+    State(state): State<AppState>,
+    auth: AuthUser,
     axum::extract::Json(data): axum::extract::Json<NewGameRequest>,
-) -> ResponseResult<axum::response::Json<Game>> {
-    match state
+) -> ResponseResult<axum::response::Json<GameInfo>> {
+    let game = state
         .manager
-        .create_game(data.host, data.mode, data.settings)
-    {
-        Ok(game) => Ok(axum::response::Json(game)),
+        .create_game(auth.user.id, data.mode, data.settings)
+        .await?;
 
-        Err(e) => match e {
-            Error::OngoingGame { host, game_id } => Err(AppError {
-                status: StatusCode::CONFLICT,
-                message: Some(format!(
-                    "User {} has an ongoing game with id {}",
-                    host, game_id
-                )),
-            }),
-
-            _ => Err(AppError {
-                status: StatusCode::INTERNAL_SERVER_ERROR,
-                message: None,
-            }),
-        },
-    }
+    Ok(axum::response::Json(game.info()))
 }
 
+/// ホストはログイン中のユーザー
 #[derive(Deserialize, Debug)]
 pub(crate) struct NewGameRequest {
-    host: UserId,
     mode: GameMode,
     settings: GameSettings,
 }
@@ -0,0 +1,11 @@
+use crate::error::ResponseResult;
+use crate::AppState;
+use axum::extract::{Path, State};
+use game::game::GameInfo;
+
+pub(crate) async fn get_game(
+    State(state): State<AppState>,
+    Path(game_id): Path<u32>,
+) -> ResponseResult<axum::response::Json<GameInfo>> {
+    Ok(axum::response::Json(state.manager.info(game_id).await?))
+}
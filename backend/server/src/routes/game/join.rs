@@ -0,0 +1,16 @@
+use crate::error::ResponseResult;
+use crate::session::AuthUser;
+use crate::AppState;
+use axum::extract::{Path, State};
+use board::board::Board;
+
+/// ログイン中のユーザーとしてゲームに参加する
+pub(crate) async fn join_game(
+    State(state): State<AppState>,
+    Path(game_id): Path<u32>,
+    auth: AuthUser,
+) -> ResponseResult<axum::response::Json<Board>> {
+    let board = state.manager.join_game(game_id, auth.user.id).await?;
+
+    Ok(axum::response::Json(board))
+}
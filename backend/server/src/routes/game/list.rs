@@ -0,0 +1,10 @@
+use crate::error::ResponseResult;
+use crate::AppState;
+use axum::extract::State;
+use game::game::GameInfo;
+
+pub(crate) async fn list_games(
+    State(state): State<AppState>,
+) -> ResponseResult<axum::response::Json<Vec<GameInfo>>> {
+    Ok(axum::response::Json(state.manager.games().await))
+}
@@ -1,9 +1,27 @@
 use crate::AppState;
-use axum::routing::post;
+use axum::routing::{get, post};
 use axum::Router;
 
+mod assets;
+mod board;
+mod code;
 mod create;
+mod info;
+mod join;
+mod list;
+mod ranking;
+mod ws;
 
 pub(crate) fn route() -> Router<AppState> {
-    Router::new().route("/new", post(create::new_game))
+    Router::new()
+        .route("/", get(list::list_games))
+        .route("/new", post(create::new_game))
+        .route("/assets", get(assets::get_assets))
+        .route("/code/:code", get(code::find_game))
+        .route("/:id", get(info::get_game))
+        .route("/:id/join", post(join::join_game))
+        .route("/:id/board", get(board::get_board))
+        .route("/:id/board/analysis", get(board::get_analysis))
+        .route("/:id/ranking", get(ranking::get_ranking))
+        .route("/:id/ws", get(ws::connect))
 }
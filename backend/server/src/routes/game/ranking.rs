@@ -0,0 +1,11 @@
+use crate::error::ResponseResult;
+use crate::AppState;
+use axum::extract::{Path, State};
+use game::ranking::Rank;
+
+pub(crate) async fn get_ranking(
+    State(state): State<AppState>,
+    Path(game_id): Path<u32>,
+) -> ResponseResult<axum::response::Json<Vec<Rank>>> {
+    Ok(axum::response::Json(state.manager.ranking(game_id).await?))
+}
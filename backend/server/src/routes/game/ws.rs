@@ -0,0 +1,479 @@
+use crate::error::ResponseResult;
+use crate::session;
+use crate::AppState;
+use axum::extract::ws::{
+    close_code, CloseFrame, Message as WsMessage, WebSocket, WebSocketUpgrade,
+};
+use axum::extract::{Path, Query, State};
+use axum::response::Response;
+use game::event::{ClientCommand, Message, ServerEvent, PROTOCOL_VERSION};
+use serde::Deserialize;
+use serenity::all::UserId;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+/// ゲームのイベントを受け取り、コマンドを送るWebSocketに接続する
+/// ホストか参加者でなければ接続できない
+pub(crate) async fn connect(
+    State(state): State<AppState>,
+    Path(game_id): Path<u32>,
+    Query(query): Query<ConnectQuery>,
+    upgrade: WebSocketUpgrade,
+) -> ResponseResult<Response> {
+    let auth = session::authenticate(&state, &query.token).await?;
+    state.manager.ensure_member(game_id, auth.user.id).await?;
+    let events = state.manager.subscribe(game_id).await?;
+
+    Ok(upgrade
+        .on_upgrade(move |socket| handle(socket, state, game_id, auth.user.id, auth.token, events)))
+}
+
+async fn handle(
+    mut socket: WebSocket,
+    state: AppState,
+    game_id: u32,
+    user: UserId,
+    token: String,
+    mut events: broadcast::Receiver<ServerEvent>,
+) {
+    loop {
+        let reply = tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => event,
+                // 追いつけなかった分は諦めて最新のイベントから送り直す
+                Err(RecvError::Lagged(skipped)) => {
+                    log::warn!("WebSocket client of game {game_id} skipped {skipped} events");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            },
+
+            message = socket.recv() => match message {
+                Some(Ok(WsMessage::Text(text))) => {
+                    // ログアウトや期限切れの後はコマンドを受け付けずに接続を閉じる
+                    if state.sessions.user(&token).await.is_none() {
+                        close_expired(&mut socket).await;
+                        break;
+                    }
+                    match handle_command(&state, game_id, user, &text).await {
+                        Some(error) => error,
+                        None => continue,
+                    }
+                }
+                Some(Ok(WsMessage::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+        };
+
+        if send(&mut socket, reply).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// クライアントからのコマンドを処理する
+/// 送信したクライアントにだけ返すべきエラーがあれば返す
+async fn handle_command(
+    state: &AppState,
+    game_id: u32,
+    user: UserId,
+    text: &str,
+) -> Option<ServerEvent> {
+    let command = match serde_json::from_str::<Message<ClientCommand>>(text) {
+        Ok(command) if command.version == PROTOCOL_VERSION => command.payload,
+        Ok(command) => {
+            return Some(ServerEvent::Error {
+                message: format!("Unsupported protocol version {}", command.version),
+            })
+        }
+        Err(e) => {
+            return Some(ServerEvent::Error {
+                message: format!("Invalid message: {e}"),
+            })
+        }
+    };
+
+    let result = match command {
+        ClientCommand::Mark { number } => {
+            state.manager.mark(game_id, user, number).await.map(|_| ())
+        }
+        ClientCommand::ClaimBingo => state.manager.claim_bingo(game_id, user).await.map(|_| ()),
+        ClientCommand::Draw { number } => {
+            state.manager.draw(game_id, user, number).await.map(|_| ())
+        }
+        ClientCommand::ChangeStatus { status } => {
+            state.manager.change_status(game_id, user, status).await
+        }
+    };
+
+    result.err().map(|e| ServerEvent::Error {
+        message: e.to_string(),
+    })
+}
+
+/// セッションが切れたことを伝えて接続を閉じる
+async fn close_expired(socket: &mut WebSocket) {
+    let message = "Session has expired";
+    let event = ServerEvent::Error {
+        message: message.to_string(),
+    };
+    if send(socket, event).await.is_ok() {
+        let frame = CloseFrame {
+            code: close_code::POLICY,
+            reason: message.into(),
+        };
+        let _ = socket.send(WsMessage::Close(Some(frame))).await;
+    }
+}
+
+async fn send(socket: &mut WebSocket, event: ServerEvent) -> Result<(), axum::Error> {
+    socket
+        .send(WsMessage::Text(
+            serde_json::to_string(&Message::new(event)).unwrap(),
+        ))
+        .await
+}
+
+/// ブラウザのWebSocketはヘッダーを付けられないため、セッショントークンはクエリで受け取る
+///
+/// URLのクエリはリバースプロキシなどのアクセスログに残るため、
+/// `/api/game/:id/ws`のログではクエリを記録しないように設定すること。
+/// 接続後もコマンドを受け取る度にセッションを確かめるので、ログアウトすれば以降のコマンドは通らない。
+#[derive(Deserialize, Debug)]
+pub(crate) struct ConnectQuery {
+    token: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::routes::app;
+    use crate::AppState;
+    use board::analysis::ReachKind;
+    use board::pattern::Pattern;
+    use futures_util::{SinkExt, StreamExt};
+    use game::config::{GameMode, GameSettings};
+    use game::event::{ClientCommand, Message, ServerEvent};
+    use game::status::GameStatus;
+    use serenity::all::UserId;
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio_tungstenite::tungstenite::http::StatusCode;
+    use tokio_tungstenite::tungstenite::{Error, Message as WsMessage};
+    use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+    type Client = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+    async fn serve(state: AppState) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app(state)).await.unwrap() });
+
+        format!("ws://{address}/api/game")
+    }
+
+    async fn receive(client: &mut Client) -> Message<ServerEvent> {
+        loop {
+            if let WsMessage::Text(text) = client.next().await.unwrap().unwrap() {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+
+    async fn send(client: &mut Client, text: String) {
+        client.send(WsMessage::Text(text)).await.unwrap();
+    }
+
+    async fn command(client: &mut Client, command: ClientCommand) {
+        send(
+            client,
+            serde_json::to_string(&Message::new(command)).unwrap(),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn it_pushes_events_to_every_client() {
+        let state = AppState::for_test();
+        let url = serve(state.clone()).await;
+
+        let (host, player) = (UserId::new(1), UserId::new(2));
+        let (host_token, player_token) = (state.login(1).await, state.login(2).await);
+        let game = state
+            .manager
+            .create_game(host, GameMode::NORMAL, GameSettings::default())
+            .await
+            .unwrap();
+        state.manager.join_game(game.id(), host).await.unwrap();
+
+        let (mut host_client, _) =
+            connect_async(format!("{url}/{}/ws?token={host_token}", game.id()))
+                .await
+                .unwrap();
+
+        let board = state.manager.join_game(game.id(), player).await.unwrap();
+        assert_eq!(
+            receive(&mut host_client).await,
+            Message::new(ServerEvent::PlayerJoined { user: player })
+        );
+        let (mut player_client, _) =
+            connect_async(format!("{url}/{}/ws?token={player_token}", game.id()))
+                .await
+                .unwrap();
+
+        let status = GameStatus::RUNNING;
+        command(&mut host_client, ClientCommand::ChangeStatus { status }).await;
+        for client in [&mut host_client, &mut player_client] {
+            assert_eq!(
+                receive(client).await,
+                Message::new(ServerEvent::StatusChanged { status })
+            );
+        }
+
+        let row = board.numbers()[0].clone();
+        for &number in &row {
+            let number = Some(number);
+            command(&mut host_client, ClientCommand::Draw { number }).await;
+        }
+        for &number in &row {
+            for client in [&mut host_client, &mut player_client] {
+                assert_eq!(
+                    receive(client).await,
+                    Message::new(ServerEvent::NumberDrawn {
+                        number,
+                        detail: None,
+                        label: None,
+                    })
+                );
+            }
+        }
+
+        for &number in &row[..row.len() - 1] {
+            command(&mut player_client, ClientCommand::Mark { number }).await;
+        }
+        assert_eq!(
+            receive(&mut host_client).await,
+            Message::new(ServerEvent::Reach {
+                user: player,
+                lines: vec![row.clone()],
+                kind: ReachKind::SINGLE,
+            })
+        );
+
+        let number = row[row.len() - 1];
+        command(&mut player_client, ClientCommand::Mark { number }).await;
+        assert_eq!(
+            receive(&mut host_client).await,
+            Message::new(ServerEvent::Bingo {
+                user: player,
+                lines: vec![row.clone()],
+                patterns: vec![Pattern::LINE],
+            })
+        );
+
+        // 開けたときに記録されたビンゴは申告し直しても他の参加者に通知されない
+        command(&mut player_client, ClientCommand::ClaimBingo).await;
+        let mut payloads = vec![];
+        for _ in 0..3 {
+            payloads.push(receive(&mut player_client).await.payload);
+        }
+        assert!(matches!(payloads[0], ServerEvent::Reach { .. }));
+        assert_eq!(
+            payloads[1],
+            ServerEvent::Bingo {
+                user: player,
+                lines: vec![row],
+                patterns: vec![Pattern::LINE],
+            }
+        );
+        assert_eq!(
+            payloads[2],
+            ServerEvent::Error {
+                message: "User 2 has already claimed the bingo".to_string()
+            }
+        );
+
+        let status = GameStatus::FINISHED;
+        command(&mut host_client, ClientCommand::ChangeStatus { status }).await;
+        assert_eq!(
+            receive(&mut host_client).await,
+            Message::new(ServerEvent::StatusChanged { status })
+        );
+        let Message { payload, .. } = receive(&mut host_client).await;
+        let ServerEvent::GameFinished { ranking } = payload else {
+            panic!("unexpected event {payload:?}");
+        };
+        assert_eq!(ranking.len(), 1);
+        assert_eq!((ranking[0].rank, ranking[0].user), (1, player));
+    }
+
+    #[tokio::test]
+    async fn it_replies_error_only_to_sender() {
+        let state = AppState::for_test();
+        let url = serve(state.clone()).await;
+
+        let game = state
+            .manager
+            .create_game(UserId::new(1), GameMode::NORMAL, GameSettings::default())
+            .await
+            .unwrap();
+        let token = state.login(2).await;
+        state
+            .manager
+            .join_game(game.id(), UserId::new(2))
+            .await
+            .unwrap();
+
+        let (mut client, _) = connect_async(format!("{url}/{}/ws?token={token}", game.id()))
+            .await
+            .unwrap();
+
+        send(&mut client, "not json".to_string()).await;
+        assert!(matches!(
+            receive(&mut client).await.payload,
+            ServerEvent::Error { .. }
+        ));
+
+        send(
+            &mut client,
+            r#"{"version":0,"type":"ClaimBingo"}"#.to_string(),
+        )
+        .await;
+        assert_eq!(
+            receive(&mut client).await.payload,
+            ServerEvent::Error {
+                message: "Unsupported protocol version 0".to_string()
+            }
+        );
+
+        command(&mut client, ClientCommand::ClaimBingo).await;
+        assert_eq!(
+            receive(&mut client).await.payload,
+            ServerEvent::Error {
+                message: "Operation not allowed while the game is LOBBY".to_string()
+            }
+        );
+
+        let status = GameStatus::RUNNING;
+        command(&mut client, ClientCommand::ChangeStatus { status }).await;
+        assert_eq!(
+            receive(&mut client).await.payload,
+            ServerEvent::Error {
+                message: "User 2 is not the host of the game".to_string()
+            }
+        );
+        state
+            .manager
+            .change_status(game.id(), UserId::new(1), status)
+            .await
+            .unwrap();
+        assert_eq!(
+            receive(&mut client).await.payload,
+            ServerEvent::StatusChanged { status }
+        );
+
+        command(&mut client, ClientCommand::Mark { number: 1 }).await;
+        assert_eq!(
+            receive(&mut client).await.payload,
+            ServerEvent::Error {
+                message: "Number 1 has not been drawn yet".to_string()
+            }
+        );
+
+        command(&mut client, ClientCommand::Draw { number: None }).await;
+        assert_eq!(
+            receive(&mut client).await.payload,
+            ServerEvent::Error {
+                message: "User 2 is not the host of the game".to_string()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn it_closes_socket_after_logout() {
+        let state = AppState::for_test();
+        let url = serve(state.clone()).await;
+
+        let host = UserId::new(1);
+        let token = state.login(1).await;
+        let game = state
+            .manager
+            .create_game(host, GameMode::NORMAL, GameSettings::default())
+            .await
+            .unwrap();
+
+        let (mut client, _) = connect_async(format!("{url}/{}/ws?token={token}", game.id()))
+            .await
+            .unwrap();
+        assert!(state.sessions.remove(&token).await);
+
+        let status = GameStatus::RUNNING;
+        command(&mut client, ClientCommand::ChangeStatus { status }).await;
+        assert_eq!(
+            receive(&mut client).await.payload,
+            ServerEvent::Error {
+                message: "Session has expired".to_string()
+            }
+        );
+        assert!(matches!(
+            client.next().await,
+            Some(Ok(WsMessage::Close(_))) | None
+        ));
+        assert_eq!(
+            state.manager.info(game.id()).await.unwrap().status,
+            GameStatus::LOBBY
+        );
+    }
+
+    #[tokio::test]
+    async fn it_rejects_unknown_game() {
+        let state = AppState::for_test();
+        let url = serve(state.clone()).await;
+        let token = state.login(2).await;
+
+        assert!(connect_async(format!("{url}/1/ws?token={token}"))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn it_rejects_non_participant() {
+        let state = AppState::for_test();
+        let url = serve(state.clone()).await;
+
+        let game = state
+            .manager
+            .create_game(UserId::new(1), GameMode::NORMAL, GameSettings::default())
+            .await
+            .unwrap();
+        let uri = format!("{url}/{}/ws?token={}", game.id(), state.login(2).await);
+
+        match connect_async(&uri).await {
+            Err(Error::Http(response)) => assert_eq!(response.status(), StatusCode::FORBIDDEN),
+            other => panic!("unexpected result {other:?}"),
+        }
+
+        state
+            .manager
+            .join_game(game.id(), UserId::new(2))
+            .await
+            .unwrap();
+        assert!(connect_async(&uri).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn it_rejects_invalid_token() {
+        let state = AppState::for_test();
+        let url = serve(state.clone()).await;
+
+        let game = state
+            .manager
+            .create_game(UserId::new(1), GameMode::NORMAL, GameSettings::default())
+            .await
+            .unwrap();
+
+        for query in ["", "?token=invalid"] {
+            assert!(connect_async(format!("{url}/{}/ws{query}", game.id()))
+                .await
+                .is_err());
+        }
+    }
+}
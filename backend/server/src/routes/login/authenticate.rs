@@ -1,4 +1,5 @@
 use crate::error::{AppError, ResponseResult};
+use crate::session::AuthUser;
 use crate::AppState;
 use axum::extract::State;
 use axum::http::StatusCode;
@@ -25,9 +26,13 @@ pub(crate) async fn get_login_url(
 pub(crate) async fn login(
     State(state): State<AppState>,
     axum::extract::Json(data): axum::extract::Json<LoginRequest>,
-) -> ResponseResult<axum::response::Json<User>> {
+) -> ResponseResult<axum::response::Json<LoginResponse>> {
     match state.oauth.get_user(data.code, data.state).await {
-        Ok(user) => Ok(axum::response::Json(user)),
+        Ok(user) => Ok(axum::response::Json(LoginResponse {
+            token: state.sessions.create(user.clone()).await,
+            expires_in: state.sessions.lifetime().as_secs(),
+            user,
+        })),
 
         Err(e) => match e {
             OAuth2Error::InvalidState { state: _ } => Err(AppError {
@@ -67,11 +72,27 @@ pub(crate) async fn login(
     }
 }
 
+/// ログイン中のセッションを破棄する
+pub(crate) async fn logout(State(state): State<AppState>, auth: AuthUser) -> StatusCode {
+    state.sessions.remove(&auth.token).await;
+
+    StatusCode::NO_CONTENT
+}
+
 #[derive(Serialize, Debug)]
 pub(crate) struct RedirectResponse {
     url: String,
 }
 
+/// 以降のリクエストで`Authorization: Bearer <token>`として送るセッショントークン
+#[derive(Serialize, Debug)]
+pub(crate) struct LoginResponse {
+    token: String,
+    /// セッションの有効期間 (秒)
+    expires_in: u64,
+    user: User,
+}
+
 #[derive(Deserialize, Debug)]
 pub(crate) struct LoginRequest {
     code: String,
@@ -7,6 +7,8 @@ mod authenticate;
 pub(crate) fn route() -> Router<AppState> {
     Router::new().route(
         "/login",
-        get(authenticate::get_login_url).post(authenticate::login),
+        get(authenticate::get_login_url)
+            .post(authenticate::login)
+            .delete(authenticate::logout),
     )
 }
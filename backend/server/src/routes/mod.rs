@@ -1,2 +1,457 @@
+use crate::AppState;
+use axum::http::StatusCode;
+use axum::Router;
+
 pub(crate) mod game;
 pub(crate) mod login;
+
+pub(crate) fn app(state: AppState) -> Router {
+    let router = Router::new()
+        .merge(login::route())
+        .nest("/game", game::route());
+
+    Router::new()
+        .nest("/api", router)
+        .fallback(|| async { StatusCode::NOT_FOUND })
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::game::status::GameStatus;
+    use axum::body::Body;
+    use axum::http::header::AUTHORIZATION;
+    use axum::http::{Method, Request};
+    use serde_json::{json, Value};
+    use serenity::all::UserId;
+    use tower::ServiceExt;
+
+    async fn request(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        body: Value,
+    ) -> (StatusCode, Value) {
+        let mut builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Content-Type", "application/json");
+        if let Some(token) = token {
+            builder = builder.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+
+        let response = app
+            .clone()
+            .oneshot(builder.body(Body::from(body.to_string())).unwrap())
+            .await
+            .unwrap();
+
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    fn setup() -> (Router, AppState) {
+        let state = AppState::for_test();
+
+        (app(state.clone()), state)
+    }
+
+    async fn create_game(app: &Router, token: &str) -> (StatusCode, Value) {
+        request(
+            app,
+            Method::POST,
+            "/api/game/new",
+            Some(token),
+            json!({
+                "mode": "NORMAL",
+                "settings": { "multiple_bingo": false, "auto_open": false, "max_player": null },
+            }),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn it_can_join_created_game() {
+        let (app, state) = setup();
+
+        let (status, game) = create_game(&app, &state.login(1).await).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(game["host"], "1");
+        assert_eq!(game["participants"], 0);
+        // 抽選順が分かる情報は返さない
+        assert!(game.get("drawer").is_none());
+
+        let (status, board) = request(
+            &app,
+            Method::POST,
+            &format!("/api/game/{}/join", game["id"]),
+            Some(&state.login(2).await),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(board["size"], 5);
+    }
+
+    #[tokio::test]
+    async fn it_requires_session() {
+        let (app, state) = setup();
+
+        for token in [None, Some("invalid")] {
+            let (status, _) = request(
+                &app,
+                Method::POST,
+                "/api/game/new",
+                token,
+                json!({ "mode": "NORMAL", "settings": {} }),
+            )
+            .await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+        }
+
+        let (_, game) = create_game(&app, &state.login(1).await).await;
+        let uri = format!("/api/game/{}", game["id"]);
+        let (status, _) = request(
+            &app,
+            Method::POST,
+            &format!("{uri}/join"),
+            None,
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = request(
+            &app,
+            Method::GET,
+            &format!("{uri}/board"),
+            None,
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn it_invalidates_session_on_logout() {
+        let (app, state) = setup();
+        let token = state.login(1).await;
+
+        let (status, _) = request(
+            &app,
+            Method::DELETE,
+            "/api/login",
+            Some(&token),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        let (status, _) = create_game(&app, &token).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = request(
+            &app,
+            Method::DELETE,
+            "/api/login",
+            Some(&token),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn it_can_list_created_game() {
+        let (app, state) = setup();
+
+        let (_, game) = create_game(&app, &state.login(1).await).await;
+        let (status, games) = request(&app, Method::GET, "/api/game", None, Value::Null).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(games.as_array().unwrap().len(), 1);
+        assert_eq!(games[0]["id"], game["id"]);
+    }
+
+    #[tokio::test]
+    async fn it_keeps_ongoing_game_between_requests() {
+        let (app, state) = setup();
+        let token = state.login(1).await;
+
+        let (status, _) = create_game(&app, &token).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, _) = create_game(&app, &token).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn it_hides_boards_from_game_list_and_code_lookup() {
+        let (app, state) = setup();
+
+        let (_, game) = create_game(&app, &state.login(1).await).await;
+        let (_, board) = request(
+            &app,
+            Method::POST,
+            &format!("/api/game/{}/join", game["id"]),
+            Some(&state.login(2).await),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(board["size"], 5);
+
+        let (_, games) = request(&app, Method::GET, "/api/game", None, Value::Null).await;
+        let code = game["code"].as_str().unwrap();
+        let (_, found) = request(
+            &app,
+            Method::GET,
+            &format!("/api/game/code/{code}"),
+            None,
+            Value::Null,
+        )
+        .await;
+
+        // 参加者の数だけを返し、他の参加者の数字盤は見せない
+        for info in [&games[0], &found] {
+            assert_eq!(info["participants"], 1);
+            assert!(!info.to_string().contains(&board["numbers"].to_string()));
+        }
+    }
+
+    #[tokio::test]
+    async fn it_returns_empty_ranking_for_new_game() {
+        let (app, state) = setup();
+
+        let (_, game) = create_game(&app, &state.login(1).await).await;
+        let (status, ranking) = request(
+            &app,
+            Method::GET,
+            &format!("/api/game/{}/ranking", game["id"]),
+            None,
+            Value::Null,
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(ranking, json!([]));
+    }
+
+    #[tokio::test]
+    async fn it_rejects_invalid_board_size() {
+        let (app, state) = setup();
+
+        let (status, _) = request(
+            &app,
+            Method::POST,
+            "/api/game/new",
+            Some(&state.login(1).await),
+            json!({
+                "mode": "NORMAL",
+                "settings": { "board_size": 4 },
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn it_rejects_too_large_number_range() {
+        let (app, state) = setup();
+
+        let (status, _) = request(
+            &app,
+            Method::POST,
+            "/api/game/new",
+            Some(&state.login(1).await),
+            json!({
+                "mode": "NORMAL",
+                "settings": { "number_range": { "min": 1, "max": 1_000_000_000_000_000_000u64 } },
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn it_rejects_asset_mode_without_assets() {
+        let (app, state) = setup();
+
+        let (status, assets) =
+            request(&app, Method::GET, "/api/game/assets", None, Value::Null).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(assets, json!({ "celebrities": [], "fonts": [] }));
+
+        let (status, _) = request(
+            &app,
+            Method::POST,
+            "/api/game/new",
+            Some(&state.login(1).await),
+            json!({ "mode": "CELEBRITY", "settings": {} }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn it_finds_game_by_join_code() {
+        let (app, state) = setup();
+
+        let (_, game) = create_game(&app, &state.login(1).await).await;
+        let code = game["code"].as_str().unwrap();
+        assert_eq!(code.len(), 6);
+
+        let (status, found) = request(
+            &app,
+            Method::GET,
+            &format!("/api/game/code/{}", code.to_lowercase()),
+            None,
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(found["id"], game["id"]);
+
+        let (status, _) =
+            request(&app, Method::GET, "/api/game/code/0000", None, Value::Null).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn it_shows_game_info() {
+        let (app, state) = setup();
+
+        let (_, game) = create_game(&app, &state.login(1).await).await;
+        let uri = format!("/api/game/{}", game["id"]);
+        request(
+            &app,
+            Method::POST,
+            &format!("{uri}/join"),
+            Some(&state.login(2).await),
+            Value::Null,
+        )
+        .await;
+
+        let (status, info) = request(&app, Method::GET, &uri, None, Value::Null).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(info["host"], "1");
+        assert_eq!(info["mode"], "NORMAL");
+        assert_eq!(info["status"], "LOBBY");
+        assert_eq!(info["participants"], 1);
+        assert_eq!(info["settings"]["board_size"], 5);
+
+        let (status, _) = request(&app, Method::GET, "/api/game/1", None, Value::Null).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn it_returns_callers_board() {
+        let (app, state) = setup();
+
+        let (_, game) = create_game(&app, &state.login(1).await).await;
+        let uri = format!("/api/game/{}", game["id"]);
+        let player = state.login(2).await;
+        let (_, joined) = request(
+            &app,
+            Method::POST,
+            &format!("{uri}/join"),
+            Some(&player),
+            Value::Null,
+        )
+        .await;
+
+        let (status, board) = request(
+            &app,
+            Method::GET,
+            &format!("{uri}/board"),
+            Some(&player),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(board, joined);
+        assert_eq!(board["opened"], json!([]));
+
+        let (status, _) = request(
+            &app,
+            Method::GET,
+            &format!("{uri}/board"),
+            Some(&state.login(3).await),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn it_analyzes_callers_board() {
+        let (app, state) = setup();
+        let (host, player) = (UserId::new(1), UserId::new(2));
+
+        let (_, game) = request(
+            &app,
+            Method::POST,
+            "/api/game/new",
+            Some(&state.login(1).await),
+            json!({ "mode": "NORMAL", "settings": { "auto_open": true } }),
+        )
+        .await;
+        let id = game["id"].as_u64().unwrap() as u32;
+        let board = state.manager.join_game(id, player).await.unwrap();
+        state
+            .manager
+            .change_status(id, host, GameStatus::RUNNING)
+            .await
+            .unwrap();
+
+        let row = board.numbers()[0].clone();
+        for &number in &row[..row.len() - 1] {
+            state.manager.draw(id, host, Some(number)).await.unwrap();
+        }
+
+        let uri = format!("/api/game/{id}/board/analysis");
+        let (status, analysis) = request(
+            &app,
+            Method::GET,
+            &uri,
+            Some(&state.login(2).await),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(analysis["winning_numbers"], json!([row[row.len() - 1]]));
+        assert_eq!(analysis["reach_kind"], "SINGLE");
+        assert_eq!(analysis["lines"].as_array().unwrap().len(), 12);
+
+        let (status, _) = request(
+            &app,
+            Method::GET,
+            &uri,
+            Some(&state.login(3).await),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn it_cannot_join_unknown_game() {
+        let (app, state) = setup();
+
+        let (status, _) = request(
+            &app,
+            Method::POST,
+            "/api/game/1/join",
+            Some(&state.login(2).await),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
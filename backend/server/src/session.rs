@@ -0,0 +1,185 @@
+use crate::error::AppError;
+use crate::AppState;
+use axum::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
+use axum::http::StatusCode;
+use oauth::User;
+use rand::distributions::{Alphanumeric, DistString};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// セッションの有効期限 (7日)
+const SESSION_LIFETIME: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// セッショントークンの長さ
+const TOKEN_LENGTH: usize = 43;
+
+#[derive(Debug)]
+struct Session {
+    user: User,
+    expires_at: Instant,
+}
+
+/// ログイン中のセッション
+///
+/// Discordでログインしたユーザーにトークンを発行し、
+/// 以降のリクエストは`Authorization: Bearer <token>`でユーザーを特定する。
+#[derive(Clone)]
+pub(crate) struct SessionStore {
+    sessions: Arc<RwLock<HashMap<String, Session>>>,
+    lifetime: Duration,
+}
+
+impl Default for SessionStore {
+    fn default() -> Self {
+        Self::with_lifetime(SESSION_LIFETIME)
+    }
+}
+
+impl SessionStore {
+    /// lifetimeで期限切れになる`SessionStore`
+    pub(crate) fn with_lifetime(lifetime: Duration) -> Self {
+        Self {
+            sessions: Arc::default(),
+            lifetime,
+        }
+    }
+
+    pub(crate) fn lifetime(&self) -> Duration {
+        self.lifetime
+    }
+
+    /// userのセッションを作り、トークンを返す
+    pub(crate) async fn create(&self, user: User) -> String {
+        let token = Alphanumeric.sample_string(&mut rand::thread_rng(), TOKEN_LENGTH);
+        let now = Instant::now();
+
+        let mut sessions = self.sessions.write().await;
+        // 期限切れのセッションはここでまとめて捨てる
+        sessions.retain(|_, session| session.expires_at > now);
+        sessions.insert(
+            token.clone(),
+            Session {
+                user,
+                expires_at: now + self.lifetime,
+            },
+        );
+
+        token
+    }
+
+    /// tokenのセッションのユーザー (存在しないか期限切れの場合はNone)
+    pub(crate) async fn user(&self, token: &str) -> Option<User> {
+        {
+            let sessions = self.sessions.read().await;
+            match sessions.get(token) {
+                Some(session) if session.expires_at > Instant::now() => {
+                    return Some(session.user.clone())
+                }
+                Some(_) => {}
+                None => return None,
+            }
+        }
+
+        self.sessions.write().await.remove(token);
+        None
+    }
+
+    /// tokenのセッションを破棄する
+    pub(crate) async fn remove(&self, token: &str) -> bool {
+        self.sessions.write().await.remove(token).is_some()
+    }
+}
+
+/// ログイン中のユーザー
+///
+/// ハンドラーの引数に置くと、有効なセッションがないリクエストは401で拒否される。
+#[derive(Debug)]
+pub(crate) struct AuthUser {
+    pub(crate) user: User,
+    pub(crate) token: String,
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for AuthUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, AppError> {
+        let token = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(unauthorized)?;
+
+        authenticate(state, token).await
+    }
+}
+
+/// tokenのセッションからユーザーを特定する
+pub(crate) async fn authenticate(state: &AppState, token: &str) -> Result<AuthUser, AppError> {
+    let user = state.sessions.user(token).await.ok_or_else(unauthorized)?;
+
+    Ok(AuthUser {
+        user,
+        token: token.to_string(),
+    })
+}
+
+fn unauthorized() -> AppError {
+    AppError {
+        status: StatusCode::UNAUTHORIZED,
+        message: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serenity::all::UserId;
+
+    fn user(id: u64) -> User {
+        User {
+            id: UserId::new(id),
+            name: format!("user{id}"),
+            avatar: String::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_finds_user_by_token() {
+        let store = SessionStore::default();
+
+        let a = store.create(user(1)).await;
+        let b = store.create(user(2)).await;
+        assert_ne!(a, b);
+        assert_eq!(a.len(), TOKEN_LENGTH);
+
+        assert_eq!(store.user(&a).await.unwrap().id, UserId::new(1));
+        assert_eq!(store.user(&b).await.unwrap().id, UserId::new(2));
+        assert!(store.user("unknown").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn it_removes_session() {
+        let store = SessionStore::default();
+        let token = store.create(user(1)).await;
+
+        assert!(store.remove(&token).await);
+        assert!(store.user(&token).await.is_none());
+        assert!(!store.remove(&token).await);
+    }
+
+    #[tokio::test]
+    async fn it_expires_session() {
+        let store = SessionStore::with_lifetime(Duration::ZERO);
+        let token = store.create(user(1)).await;
+
+        assert!(store.user(&token).await.is_none());
+        assert!(store.sessions.read().await.is_empty());
+    }
+}